@@ -1,24 +1,149 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
-use aoc_2020::day01::*;
+use aoc_2020::{
+    day01::{self, Day01},
+    day02::{self, Day02},
+    day03::{self, Day03},
+    day04::Day04,
+    day05::Day05,
+    day06::{self, Day06},
+    day07::Day07,
+    inputs, Solution,
+};
 
-// TODO: impl with inputs?
 criterion_group!(benches, criterion_benchmark);
 criterion_main!(benches);
 
 pub fn criterion_benchmark(c: &mut Criterion) {
-    //     {
-    //         let mut pair_impls = c.benchmark_group("Pair");
-
-    //         pair_impls.bench_function("naive", |b| {
-    //             b.iter(|| find_pair_with_sum_naive(&INPUTS, TARGET_SUM))
-    //         });
-    //         pair_impls.bench_function("itertools", |b| {
-    //             b.iter(|| find_pair_with_sum(&INPUTS, TARGET_SUM))
-    //         });
-    //     }
-
-    //     c.bench_function("triple", |b| {
-    //         b.iter(|| find_triple_with_sum(&INPUTS, TARGET_SUM))
-    //     });
+    bench_solution::<Day01>(c);
+    bench_solution::<Day02>(c);
+    bench_solution::<Day03>(c);
+    bench_solution::<Day04>(c);
+    bench_solution::<Day05>(c);
+    bench_solution::<Day06>(c);
+    bench_solution::<Day07>(c);
+
+    {
+        let input = inputs::load(1).expect("Failed to load input");
+        let entries = Day01::parse(&input).expect("Failed to parse input");
+        let mut pair_impls = c.benchmark_group("day01/pair");
+
+        pair_impls.bench_function("naive", |b| {
+            b.iter(|| day01::find_pair_with_sum_naive(black_box(&entries), day01::TARGET_SUM))
+        });
+        pair_impls.bench_function("itertools", |b| {
+            b.iter(|| day01::find_pair_with_sum(black_box(&entries), day01::TARGET_SUM))
+        });
+        pair_impls.bench_function("hashset", |b| {
+            b.iter(|| day01::find_pair_with_sum_hashed(black_box(&entries), day01::TARGET_SUM))
+        });
+        pair_impls.bench_function("two-pointer", |b| {
+            b.iter(|| day01::find_pair_with_sum_sorted(black_box(&entries), day01::TARGET_SUM))
+        });
+        pair_impls.finish();
+
+        let mut triple_impls = c.benchmark_group("day01/triple");
+        triple_impls.bench_function("itertools", |b| {
+            b.iter(|| day01::find_triple_with_sum(black_box(&entries), day01::TARGET_SUM))
+        });
+        triple_impls.bench_function("k-sum", |b| {
+            b.iter(|| day01::find_k_with_sum(black_box(&entries), 3, day01::TARGET_SUM))
+        });
+        triple_impls.finish();
+    }
+
+    {
+        let input = inputs::load(2).expect("Failed to load input");
+        let mut parse_impls = c.benchmark_group("day02/parse");
+
+        parse_impls.bench_function("naive", |b| {
+            b.iter(|| day02::Entry::parse_naive(black_box(&input)).count())
+        });
+        parse_impls.bench_function("nom", |b| {
+            b.iter(|| day02::Entry::parse_iter_from(black_box(&input)).count())
+        });
+        parse_impls.finish();
+
+        let entries = Day02::parse(&input).expect("Failed to parse input");
+        let mut mode_impls = c.benchmark_group("day02/mode");
+        for mode in [
+            day02::Mode::Bytes,
+            day02::Mode::Chars,
+            day02::Mode::Graphemes,
+        ] {
+            mode_impls.bench_function(format!("{:?}", mode).to_lowercase(), |b| {
+                b.iter(|| {
+                    black_box(&entries)
+                        .iter()
+                        .filter(|entry| entry.validate_2_in(mode).is_valid())
+                        .count()
+                })
+            });
+        }
+        mode_impls.finish();
+    }
+
+    {
+        let input = inputs::load(3).expect("Failed to load input");
+        let field = day03::Field::new(&input);
+        let mut traverse_impls = c.benchmark_group("day03/traverse");
+
+        traverse_impls.bench_function("cycle", |b| {
+            b.iter(|| black_box(&field).traverse_cycle(1, 3))
+        });
+        traverse_impls.bench_function("collect", |b| {
+            b.iter(|| black_box(&field).traverse_collect(1, 3))
+        });
+
+        let grid = day03::Grid::try_from(&field).expect("Failed to parse input");
+        traverse_impls.bench_function("grid-cycle", |b| {
+            b.iter(|| black_box(&grid).traverse_cycle(1, 3))
+        });
+        traverse_impls.bench_function("grid-collect", |b| {
+            b.iter(|| black_box(&grid).traverse_collect(1, 3))
+        });
+
+        let bits = day03::BitGrid::from(&grid);
+        traverse_impls.bench_function("bitset", |b| b.iter(|| black_box(&bits).traverse(1, 3)));
+        traverse_impls.finish();
+
+        c.bench_function("day03/best-slopes", |b| {
+            b.iter(|| black_box(&grid).best_slopes(1..=10, -50..=50, day03::Objective::Fewest))
+        });
+    }
+
+    {
+        let input = inputs::load(6).expect("Failed to load input");
+        let mut set_impls = c.benchmark_group("day06/sets");
+
+        set_impls.bench_function("hashset", |b| {
+            b.iter(|| {
+                day06::Group::parse_iter_from(black_box(&input))
+                    .map(|g| (g.count_anyone(), g.count_everyone()))
+                    .fold((0, 0), |(a, e), (ga, ge)| (a + ga, e + ge))
+            })
+        });
+        set_impls.bench_function("bitmask", |b| {
+            b.iter(|| {
+                day06::PackedGroup::parse_all(black_box(&input))
+                    .expect("Failed to parse input")
+                    .iter()
+                    .map(|g| (g.anyone().len(), g.everyone().len()))
+                    .fold((0, 0), |(a, e), (ga, ge)| (a + ga, e + ge))
+            })
+        });
+        set_impls.finish();
+    }
+}
+
+/// Benchmark parsing and both parts of a day against its real input
+fn bench_solution<S: Solution>(c: &mut Criterion) {
+    let input = inputs::load(S::DAY).expect("Failed to load input");
+    let parsed = S::parse(&input).expect("Failed to parse input");
+    let mut group = c.benchmark_group(format!("day{:02}", S::DAY));
+
+    group.bench_function("parse", |b| b.iter(|| S::parse(black_box(&input))));
+    group.bench_function("part1", |b| b.iter(|| S::part1(black_box(&parsed))));
+    group.bench_function("part2", |b| b.iter(|| S::part2(black_box(&parsed))));
+    group.finish();
 }
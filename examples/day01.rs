@@ -1,7 +1,4 @@
-use aoc_2020::day01::*;
-
-const INPUT: &str = include_str!("../inputs/01");
-const TARGET_SUM: usize = 2020;
+use aoc_2020::{day01::*, inputs};
 
 fn main() {
     let inputs = parse_input();
@@ -18,10 +15,11 @@ fn main() {
 }
 
 fn parse_input() -> Vec<usize> {
-    INPUT
-        .lines()
-        .map(|line| line.parse().expect("Failed to parse"))
-        .collect()
+    let input = inputs::load(1).expect("Failed to load input");
+    match parse_entries(&input) {
+        Ok(entries) => entries,
+        Err(e) => panic!("{}", e),
+    }
 }
 
 #[cfg(test)]
@@ -1,21 +1,34 @@
-use aoc_2020::day02::*;
-
-pub const INPUT: &str = include_str!("../inputs/02");
+use aoc_2020::{day02::*, inputs};
 
 fn main() {
-    let entries = parse_input();
+    let input = inputs::load(2).expect("Failed to load input");
+    let entries = parse_input(&input);
 
     println!("Part 1:");
     let valid = entries.clone().filter(Entry::validate_1).count();
     println!("\tFound\t{} valid entries", valid);
 
     println!("Part 2:");
-    let valid = entries.filter(Entry::validate_2).count();
+    let (valid, invalid, malformed) = count_validity(entries);
     println!("\tFound\t{} valid entries", valid);
+    println!("\t\t{} invalid entries", invalid);
+    println!("\t\t{} entries with malformed policies", malformed);
+}
+
+fn parse_input(input: &str) -> impl Iterator<Item = Entry<'_>> + Clone {
+    Entry::parse_iter_from(input).map(|entry| entry.expect("Failed to parse input"))
 }
 
-fn parse_input() -> impl Iterator<Item = Entry<'static>> + Clone {
-    Entry::parse_naive(INPUT).map(|entry| entry.expect("Failed to parse input"))
+/// Count the (valid, invalid, malformed) entries for part 2
+fn count_validity<'a>(entries: impl Iterator<Item = Entry<'a>>) -> (usize, usize, usize) {
+    entries.fold(
+        (0, 0, 0),
+        |(valid, invalid, malformed), entry| match entry.validate_2() {
+            Validity::Valid => (valid + 1, invalid, malformed),
+            Validity::Invalid => (valid, invalid + 1, malformed),
+            Validity::Malformed(_) => (valid, invalid, malformed + 1),
+        },
+    )
 }
 
 #[cfg(test)]
@@ -24,11 +37,13 @@ mod tests {
 
     #[test]
     fn part_1() {
-        assert_eq!(parse_input().filter(Entry::validate_1).count(), 660)
+        let input = inputs::load(2).expect("Failed to load input");
+        assert_eq!(parse_input(&input).filter(Entry::validate_1).count(), 660)
     }
 
     #[test]
     fn part_2() {
-        assert_eq!(parse_input().filter(Entry::validate_2).count(), 530)
+        let input = inputs::load(2).expect("Failed to load input");
+        assert_eq!(count_validity(parse_input(&input)), (530, 470, 0))
     }
 }
@@ -1,12 +1,11 @@
-use aoc_2020::day03::*;
-
-pub const INPUT: &str = include_str!("../inputs/03");
+use aoc_2020::{day03::*, inputs};
 
 pub const SLOPE_RUN: usize = 3;
 pub const SLOPE_RISE: usize = 1;
 
 fn main() {
-    let field = Field::new(INPUT);
+    let input = inputs::load(3).expect("Failed to load input");
+    let field = Field::new(&input);
 
     println!("Part 1:");
     let path_1_3 = field.traverse_cycle(1, 3);
@@ -29,7 +28,19 @@ fn main() {
     println!(
         "\tProduct = {}",
         path_1_1 * path_1_3 * path_1_5 * path_1_7 * path_2_1
-    )
+    );
+
+    println!("Best slopes (down 1-10, right -50-50):");
+    let grid = Grid::try_from(&field).expect("Failed to parse input");
+    for objective in [Objective::Fewest, Objective::Most] {
+        let best = grid
+            .best_slopes(1..=10, -50..=50, objective)
+            .expect("No slopes searched");
+        println!("\t{:?}: {} trees", objective, best.trees);
+        for slope in best.slopes {
+            println!("\t\tRight {}, Down {}", slope.right, slope.down);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -38,14 +49,16 @@ mod tests {
 
     #[test]
     fn part_1() {
-        let field = Field::new(INPUT);
+        let input = inputs::load(3).expect("Failed to load input");
+        let field = Field::new(&input);
         assert_eq!(field.traverse_cycle(1, 3), 148);
         assert_eq!(field.traverse_collect(1, 3), 148)
     }
 
     #[test]
     fn part_2() {
-        let field = Field::new(INPUT);
+        let input = inputs::load(3).expect("Failed to load input");
+        let field = Field::new(&input);
         assert_eq!(field.traverse_cycle(1, 1), 50);
         assert_eq!(field.traverse_cycle(1, 3), 148);
         assert_eq!(field.traverse_cycle(1, 5), 53);
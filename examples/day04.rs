@@ -1,10 +1,9 @@
-use aoc_2020::day04::*;
+use aoc_2020::{day04::*, inputs};
 use validator::Validate;
 
-pub const INPUT: &str = include_str!("../inputs/04");
-
 fn main() {
-    let raw = RawPassport::parse_iter_from(INPUT).filter_map(Result::ok);
+    let input = inputs::load(4).expect("Failed to load input");
+    let raw = RawPassport::parse_iter_from(&input).filter_map(Result::ok);
 
     println!("Part 1:");
     let valid = raw.clone().count();
@@ -15,8 +14,7 @@ fn main() {
         .filter_map(|r| {
             Passport::try_from(r)
                 .ok()
-                .map(|p| p.validate().map(|_| p).ok())
-                .flatten()
+                .and_then(|p| p.validate().map(|_| p).ok())
         })
         .count();
 
@@ -29,8 +27,9 @@ mod tests {
 
     #[test]
     fn part_1() {
+        let input = inputs::load(4).expect("Failed to load input");
         assert_eq!(
-            RawPassport::parse_iter_from(INPUT)
+            RawPassport::parse_iter_from(&input)
                 .filter_map(Result::ok)
                 .count(),
             260
@@ -39,9 +38,10 @@ mod tests {
 
     #[test]
     fn part_2() {
+        let input = inputs::load(4).expect("Failed to load input");
         assert_eq!(
-            Passport::parse_iter_from(INPUT)
-                .filter_map(|p| p.ok().map(|p| p.validate().ok()).flatten())
+            Passport::parse_iter_from(&input)
+                .filter_map(|p| p.ok().and_then(|p| p.validate().ok()))
                 .count(),
             153
         );
@@ -1,22 +1,20 @@
-use std::{collections::BTreeSet, str::FromStr};
+use std::collections::BTreeSet;
 
-use aoc_2020::day05::*;
-
-pub const INPUT: &str = include_str!("../inputs/05");
+use aoc_2020::{day05::*, inputs};
 
 fn main() {
-    let seat_ids = INPUT
-        .lines()
-        .filter_map(|line| BoardingPass::from_str(line).ok())
-        .map(|bp| bp.seat_id());
+    let input = inputs::load(5).expect("Failed to load input");
+    let seat_ids = BoardingPass::read_from(input.as_bytes())
+        .map(|bp| bp.expect("Failed to parse input").seat_id())
+        .collect::<Vec<_>>();
 
     println!("Part 1:");
-    let max = seat_ids.clone().max().expect("No max found");
+    let max = seat_ids.iter().max().expect("No max found");
 
     println!("\tFound max seat id: {}", max);
 
     println!("Part 2:");
-    let ids = seat_ids.collect::<BTreeSet<_>>();
+    let ids = seat_ids.into_iter().collect::<BTreeSet<_>>();
     let (before, _after) = ids
         .iter()
         .zip(ids.iter().skip(1))
@@ -28,12 +26,15 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
     use super::*;
 
     #[test]
     fn part_1() {
+        let input = inputs::load(5).expect("Failed to load input");
         assert_eq!(
-            INPUT
+            input
                 .lines()
                 .filter_map(|line| BoardingPass::from_str(line).ok())
                 .map(|bp| bp.seat_id())
@@ -45,7 +46,8 @@ mod tests {
 
     #[test]
     fn part_2() {
-        let ids = INPUT
+        let input = inputs::load(5).expect("Failed to load input");
+        let ids = input
             .lines()
             .filter_map(|line| BoardingPass::from_str(line).ok())
             .map(|bp| bp.seat_id())
@@ -1,41 +1,18 @@
-use std::collections::HashSet;
-
-// use aoc_2020::day06::*;
-
-pub const INPUT: &str = include_str!("../inputs/06");
+use aoc_2020::{day06::*, inputs};
 
 fn main() {
-    let groups = INPUT.split("\n\n");
+    let input = inputs::load(6).expect("Failed to load input");
+    let groups = Group::parse_iter_from(&input);
 
     println!("Part 1:");
     let sum = groups
         .clone()
-        .map(|group| {
-            group
-                .lines()
-                .fold(HashSet::new(), |mut answers, person_response| {
-                    answers.extend(person_response.chars());
-                    answers
-                })
-                .len()
-        })
+        .map(|group| group.count_anyone())
         .sum::<usize>();
-
     println!("\tSum: {}", sum);
 
     println!("Part 2:");
-    let sum = groups
-        .map(|group| {
-            let mut responses = group
-                .lines()
-                .map(|person_response| person_response.chars().collect::<HashSet<_>>());
-            let mut all = responses.next().expect("No groups");
-            for next in responses {
-                all = all.intersection(&next).cloned().collect();
-            }
-            all.len()
-        })
-        .sum::<usize>();
+    let sum = groups.map(|group| group.count_everyone()).sum::<usize>();
     println!("\tSum: {}", sum);
 }
 
@@ -43,13 +20,25 @@ fn main() {
 mod tests {
     use super::*;
 
-    // #[test]
-    // fn part_1() {
-    //     assert_eq!(todo!(), 6437);
-    // }
-
-    // #[test]
-    // fn part_2() {
-    //     assert_eq!(todo!(), 3229);
-    // }
+    #[test]
+    fn part_1() {
+        let input = inputs::load(6).expect("Failed to load input");
+        assert_eq!(
+            Group::parse_iter_from(&input)
+                .map(|group| group.count_anyone())
+                .sum::<usize>(),
+            6437
+        );
+    }
+
+    #[test]
+    fn part_2() {
+        let input = inputs::load(6).expect("Failed to load input");
+        assert_eq!(
+            Group::parse_iter_from(&input)
+                .map(|group| group.count_everyone())
+                .sum::<usize>(),
+            3229
+        );
+    }
 }
@@ -1,9 +1,8 @@
-use aoc_2020::day07::*;
-
-pub const INPUT: &str = include_str!("../inputs/07");
+use aoc_2020::{day07::*, inputs};
 
 fn main() {
-    let rules = INPUT
+    let input = inputs::load(7).expect("Failed to load input");
+    let rules = input
         .lines()
         .map(|input| Rule::try_from(input).expect("Failed to parse"))
         .collect::<Tree<'_>>();
@@ -29,8 +28,9 @@ mod tests {
 
     #[test]
     fn part_1() {
+        let input = inputs::load(7).expect("Failed to load input");
         assert_eq!(
-            INPUT
+            input
                 .lines()
                 .map(|input| Rule::try_from(input).expect("Failed to parse"))
                 .collect::<Tree<'_>>()
@@ -41,8 +41,17 @@ mod tests {
         );
     }
 
-    // #[test]
-    // fn part_2() {
-    //     assert_eq!(todo!(), 3229);
-    // }
+    #[test]
+    fn part_2() {
+        let input = inputs::load(7).expect("Failed to load input");
+        assert_eq!(
+            input
+                .lines()
+                .map(|input| Rule::try_from(input).expect("Failed to parse"))
+                .collect::<Tree<'_>>()
+                .count_contents_of(&Color("shiny gold"))
+                .expect("Shiny gold not found!"),
+            18885
+        );
+    }
 }
@@ -0,0 +1,233 @@
+//! Registry of known-good answers, and verification of every day against it
+
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use serde::Deserialize;
+
+use crate::{
+    inputs::Inputs,
+    runner::{self, Part},
+};
+
+/// The registry checked in alongside the inputs
+pub const REGISTRY: &str = include_str!("../answers.toml");
+
+/// Expected answers keyed by day, parsed from TOML in the form:
+///
+/// ```toml
+/// [day01]
+/// part1 = 913824
+/// part2 = "a string answer"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers(BTreeMap<u8, DayAnswers>);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct DayAnswers {
+    #[serde(default, deserialize_with = "answer")]
+    part1: Option<String>,
+    #[serde(default, deserialize_with = "answer")]
+    part2: Option<String>,
+}
+
+/// Answers may be written as integers or strings, but are compared as text
+fn answer<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Int(i64),
+        Str(String),
+    }
+
+    Ok(Some(match Raw::deserialize(d)? {
+        Raw::Int(n) => n.to_string(),
+        Raw::Str(s) => s,
+    }))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnswersError {
+    #[error("Failed to read answers {}: {source}", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Malformed answers: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("Invalid day key \"{0}\" (expected e.g. \"day01\")")]
+    DayKey(String),
+}
+
+impl Answers {
+    /// The checked-in [`REGISTRY`]
+    pub fn registry() -> Self {
+        Self::parse(REGISTRY).expect("answers.toml is malformed")
+    }
+
+    pub fn parse(s: &str) -> Result<Self, AnswersError> {
+        toml::from_str::<BTreeMap<String, DayAnswers>>(s)?
+            .into_iter()
+            .map(|(key, answers)| {
+                key.strip_prefix("day")
+                    .and_then(|day| day.parse().ok())
+                    .map(|day| (day, answers))
+                    .ok_or(AnswersError::DayKey(key))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AnswersError> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path).map_err(|source| AnswersError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        Self::parse(&s)
+    }
+
+    /// The expected answer for a part, if known
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        let answers = self.0.get(&day)?;
+        match part {
+            Part::One => answers.part1.as_deref(),
+            Part::Two => answers.part2.as_deref(),
+        }
+    }
+}
+
+/// The result of checking one part against the registry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// The part produced an answer, but there's nothing to check it against
+    Missing {
+        actual: String,
+    },
+    /// The part (or its input) failed before producing an answer
+    Error(String),
+}
+
+impl Verdict {
+    /// Whether this verdict should be treated as a regression
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::Fail { .. } | Verdict::Error(_))
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected, actual } => {
+                write!(f, "FAIL (expected {}, got {})", expected, actual)
+            }
+            Verdict::Missing { actual } => write!(f, "missing (got {})", actual),
+            Verdict::Error(e) => write!(f, "ERROR ({})", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub day: u8,
+    pub part: Part,
+    pub verdict: Verdict,
+}
+
+/// Run every implemented day on its input and compare against `answers`
+pub fn verify(answers: &Answers, inputs: &Inputs) -> Vec<Check> {
+    runner::DAYS
+        .iter()
+        .flat_map(|day| {
+            let outcome = inputs
+                .load(day.day)
+                .map_err(|e| e.to_string())
+                .and_then(|input| day.run(&input, &Part::ALL).map_err(|e| e.to_string()));
+
+            let verdicts = match outcome {
+                Ok(outcome) => outcome
+                    .parts
+                    .into_iter()
+                    .map(|p| {
+                        let verdict = match (p.answer, answers.get(day.day, p.part)) {
+                            (Err(e), _) => Verdict::Error(e.to_string()),
+                            (Ok(actual), None) => Verdict::Missing { actual },
+                            (Ok(actual), Some(expected)) if actual == expected => Verdict::Pass,
+                            (Ok(actual), Some(expected)) => Verdict::Fail {
+                                expected: expected.to_string(),
+                                actual,
+                            },
+                        };
+                        (p.part, verdict)
+                    })
+                    .collect(),
+                Err(e) => Part::ALL
+                    .iter()
+                    .map(|&part| (part, Verdict::Error(e.clone())))
+                    .collect::<Vec<_>>(),
+            };
+
+            verdicts.into_iter().map(move |(part, verdict)| Check {
+                day: day.day,
+                part,
+                verdict,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let answers = Answers::parse("[day02]\npart1 = 12\n\n[day10]\npart2 = \"abc\"\n")
+            .expect("Failed to parse");
+        assert_eq!(answers.get(2, Part::One), Some("12"));
+        assert_eq!(answers.get(2, Part::Two), None);
+        assert_eq!(answers.get(10, Part::Two), Some("abc"));
+
+        assert!(matches!(
+            Answers::parse("[two]\npart1 = 1"),
+            Err(AnswersError::DayKey(key)) if key == "two"
+        ));
+    }
+
+    #[test]
+    fn registry_passes() {
+        let checks = verify(&Answers::registry(), &Inputs::from_env());
+        assert_eq!(checks.len(), runner::DAYS.len() * 2);
+        for check in checks {
+            assert_eq!(check.verdict, Verdict::Pass, "{:?}", check);
+        }
+    }
+
+    #[test]
+    fn missing_and_failing() {
+        let answers = Answers::parse("[day05]\npart1 = 1").expect("Failed to parse");
+        let checks = verify(&answers, &Inputs::from_env());
+
+        let day5 = checks.iter().filter(|c| c.day == 5).collect::<Vec<_>>();
+        assert_eq!(
+            day5[0].verdict,
+            Verdict::Fail {
+                expected: "1".into(),
+                actual: "933".into()
+            }
+        );
+        assert_eq!(
+            day5[1].verdict,
+            Verdict::Missing {
+                actual: "711".into()
+            }
+        );
+    }
+}
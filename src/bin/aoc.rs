@@ -0,0 +1,224 @@
+use std::{
+    io::Read,
+    iter::Peekable,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use aoc_2020::{
+    answers::{self, Answers},
+    inputs::Inputs,
+    runner::{self, BoxError, DayOutcome, Part},
+};
+
+#[cfg(not(feature = "read_inputs"))]
+const USAGE: &str = "\
+Usage:
+    aoc <day> [part] [--input <path>]    Run one day (both parts unless given)
+    aoc all                               Run every implemented day
+    aoc verify [--answers <path>]         Check every day against known answers
+
+The input defaults to the one embedded for <day>. Pass `--input -` to read
+from stdin.
+
+Answers default to the checked-in answers.toml registry.";
+
+#[cfg(feature = "read_inputs")]
+const USAGE: &str = "\
+Usage:
+    aoc [--inputs <dir>] <day> [part] [--input <path>]
+    aoc [--inputs <dir>] all
+    aoc [--inputs <dir>] verify [--answers <path>]
+
+The input defaults to <dir>/<day>, where <dir> is taken from $AOC_INPUTS if
+not given. Pass `--input -` to read from stdin.
+
+Answers default to the checked-in answers.toml registry.";
+
+/// What the runner was asked to do
+enum Command {
+    Day {
+        day: u8,
+        parts: Vec<Part>,
+        input: Option<PathBuf>,
+    },
+    All,
+    Verify {
+        answers: Option<PathBuf>,
+    },
+}
+
+fn main() -> ExitCode {
+    let (inputs, command) = match parse_args(std::env::args().skip(1)) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = match command {
+        Command::Day { day, parts, input } => run_day(&inputs, day, &parts, input.as_deref()),
+        Command::All => Ok(run_all(&inputs)),
+        Command::Verify { answers } => verify(&inputs, answers.as_deref()),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<(Inputs, Command), String> {
+    let mut args = args.peekable();
+    let inputs = parse_inputs(&mut args)?;
+    let first = args.next().ok_or("Missing day")?;
+
+    if first == "all" {
+        return match args.next() {
+            None => Ok((inputs, Command::All)),
+            Some(extra) => Err(format!("Unexpected argument \"{}\"", extra)),
+        };
+    }
+
+    if first == "verify" {
+        let mut answers = None;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--answers" => {
+                    answers = Some(args.next().ok_or("Missing path after --answers")?.into());
+                }
+                extra => return Err(format!("Unexpected argument \"{}\"", extra)),
+            }
+        }
+        return Ok((inputs, Command::Verify { answers }));
+    }
+
+    let day = first
+        .parse()
+        .map_err(|_| format!("Invalid day \"{}\"", first))?;
+    let mut parts = Vec::new();
+    let mut input = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => {
+                input = Some(args.next().ok_or("Missing path after --input")?.into());
+            }
+            part if parts.is_empty() => {
+                parts.push(part.parse().map_err(|e| format!("{}", e))?);
+            }
+            extra => return Err(format!("Unexpected argument \"{}\"", extra)),
+        }
+    }
+
+    if parts.is_empty() {
+        parts.extend(Part::ALL);
+    }
+
+    Ok((inputs, Command::Day { day, parts, input }))
+}
+
+#[cfg(feature = "read_inputs")]
+fn parse_inputs(args: &mut Peekable<impl Iterator<Item = String>>) -> Result<Inputs, String> {
+    if args.next_if(|arg| arg == "--inputs").is_some() {
+        let root = args.next().ok_or("Missing directory after --inputs")?;
+        Ok(Inputs::with_root(root))
+    } else {
+        Ok(Inputs::from_env())
+    }
+}
+
+#[cfg(not(feature = "read_inputs"))]
+fn parse_inputs(_args: &mut Peekable<impl Iterator<Item = String>>) -> Result<Inputs, String> {
+    Ok(Inputs::from_env())
+}
+
+fn read_input(path: &Path) -> std::io::Result<String> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        std::fs::read_to_string(path)
+    }
+}
+
+/// Run a day and print its answers, returning whether every part succeeded.
+///
+/// Without an explicit `path`, the day's input is loaded from `inputs`.
+fn run_day(
+    inputs: &Inputs,
+    day: u8,
+    parts: &[Part],
+    path: Option<&Path>,
+) -> Result<bool, BoxError> {
+    let solution = runner::day(day)?;
+    let input = match path {
+        Some(path) => read_input(path)
+            .map_err(|e| format!("Failed to read input {}: {}", path.display(), e))?
+            .into(),
+        None => inputs.load(day)?,
+    };
+    let outcome = solution.run(&input, parts)?;
+    Ok(print_outcome(&outcome))
+}
+
+/// Run every implemented day, reporting any that can't be run and carrying on
+/// with the rest. Returns whether every day succeeded.
+fn run_all(inputs: &Inputs) -> bool {
+    let mut ok = true;
+    for day in runner::DAYS {
+        match run_day(inputs, day.day, &Part::ALL, None) {
+            Ok(day_ok) => ok &= day_ok,
+            Err(e) => {
+                ok = false;
+                println!("Day {:02}:\tFailed: {}", day.day, e);
+            }
+        }
+    }
+    ok
+}
+
+fn print_outcome(outcome: &DayOutcome) -> bool {
+    println!(
+        "Day {:02} (parsed in {:?})",
+        outcome.day, outcome.parse_elapsed
+    );
+
+    let mut ok = true;
+    for part in &outcome.parts {
+        match &part.answer {
+            Ok(answer) => println!("\tPart {}:\t{}\t({:?})", part.part, answer, part.elapsed),
+            Err(e) => {
+                ok = false;
+                println!("\tPart {}:\tFailed: {}\t({:?})", part.part, e, part.elapsed)
+            }
+        }
+    }
+    ok
+}
+
+/// Check every day against the answer registry, returning whether none failed
+fn verify(inputs: &Inputs, path: Option<&Path>) -> Result<bool, BoxError> {
+    let answers = match path {
+        Some(path) => Answers::load(path)?,
+        None => Answers::registry(),
+    };
+
+    let checks = answers::verify(&answers, inputs);
+    for check in &checks {
+        println!(
+            "Day {:02} Part {}:\t{}",
+            check.day, check.part, check.verdict
+        );
+    }
+
+    let failures = checks.iter().filter(|c| c.verdict.is_failure()).count();
+    println!("\n{} of {} parts failed", failures, checks.len());
+    Ok(failures == 0)
+}
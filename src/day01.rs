@@ -1,22 +1,144 @@
-use itertools::Itertools;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    hash::Hash,
+    mem::size_of,
+    num::ParseIntError,
+    str::FromStr,
+};
 
-pub fn find_pair_with_sum_naive(inputs: &[usize], target: usize) -> Option<(usize, usize)> {
+use itertools::{Either, Itertools};
+
+use crate::Solution;
+
+/// The sum the expense report entries need to add up to
+pub const TARGET_SUM: usize = 2020;
+
+/// Integer types that expense report entries can be searched as.
+///
+/// Every sum is computed with checked arithmetic, so a tuple is never reported
+/// as a match because its sum wrapped around to the target. Sums are ordered so
+/// that a tuple whose total fits in the type is still found when a partial sum
+/// along the way wouldn't have (e.g. `[i64::MAX, 1, -2]`).
+pub trait Amount: Copy + Ord + Hash + fmt::Debug {
+    const ZERO: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_amount {
+    ($($t:ty)*) => {$(
+        impl Amount for $t {
+            const ZERO: Self = 0;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+        }
+    )*};
+}
+
+impl_amount!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+/// `a + b + c`, or `None` if the total doesn't fit in `T`
+fn checked_sum3<T: Amount>(a: T, b: T, c: T) -> Option<T> {
+    // If `a + b` overflows they share a sign, so `c` must have the other one
+    // for the total to fit, and `b + c` can't overflow
+    a.checked_add(b)
+        .and_then(|ab| ab.checked_add(c))
+        .or_else(|| b.checked_add(c).and_then(|bc| bc.checked_add(a)))
+}
+
+/// Which end of the range of `T` a result overflowed past
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Overflow {
+    AboveMax,
+    BelowMin,
+}
+
+/// `target - a - b`, or which way it overflowed if it doesn't fit in `T`
+fn checked_rest<T: Amount>(target: T, a: T, b: T) -> Result<T, Overflow> {
+    match a.checked_add(b) {
+        // Subtracting a negative can only overflow upwards, and a positive
+        // downwards
+        Some(ab) => target.checked_sub(ab).ok_or(if ab < T::ZERO {
+            Overflow::AboveMax
+        } else {
+            Overflow::BelowMin
+        }),
+        // `a` and `b` share a sign, so if `target - a` overflows then the
+        // final result overflows the same way
+        None => target
+            .checked_sub(a)
+            .and_then(|rest| rest.checked_sub(b))
+            .ok_or(if a < T::ZERO {
+                Overflow::AboveMax
+            } else {
+                Overflow::BelowMin
+            }),
+    }
+}
+
+/// The sum of a slice, if it could be computed in order without overflowing
+fn checked_sum<T: Amount>(values: &[T]) -> Option<T> {
+    values
+        .iter()
+        .try_fold(T::ZERO, |sum, &x| sum.checked_add(x))
+}
+
+/// Parse an expense report with one entry per line.
+///
+/// Blank lines are skipped, and anything after a `#` is a comment.
+pub fn parse_entries<T>(input: &str) -> Result<Vec<T>, ParseError>
+where
+    T: Amount + FromStr<Err = ParseIntError>,
+{
+    (1..)
+        .zip(input.lines())
+        .filter_map(|(line, text)| {
+            let entry = text.split_once('#').map_or(text, |(entry, _)| entry).trim();
+            if entry.is_empty() {
+                None
+            } else {
+                Some(entry.parse().map_err(|source| ParseError {
+                    line,
+                    text: text.to_string(),
+                    source,
+                }))
+            }
+        })
+        .collect()
+}
+
+/// An entry that isn't an integer, on a 1-indexed line
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Invalid entry on line {line} \"{text}\": {source}")]
+pub struct ParseError {
+    pub line: usize,
+    pub text: String,
+    pub source: ParseIntError,
+}
+
+pub fn find_pair_with_sum_naive<T: Amount>(inputs: &[T], target: T) -> Option<(T, T)> {
     // TODO: or use itertools::tuple_combinations
-    let mut skip = 1;
-    for x in inputs.iter() {
+    for (skip, x) in (1..).zip(inputs.iter()) {
         for y in inputs.iter().skip(skip) {
-            if x + y == target {
+            if x.checked_add(*y) == Some(target) {
                 return Some((*x, *y));
             }
         }
-        skip += 1;
     }
     None
 }
 
-pub fn find_pair_with_sum(inputs: &[usize], target: usize) -> Option<(usize, usize)> {
+pub fn find_pair_with_sum<T: Amount>(inputs: &[T], target: T) -> Option<(T, T)> {
     inputs.iter().tuple_combinations().find_map(|(a, b)| {
-        if a + b == target {
+        if a.checked_add(*b) == Some(target) {
             Some((*a, *b))
         } else {
             None
@@ -24,12 +146,1222 @@ pub fn find_pair_with_sum(inputs: &[usize], target: usize) -> Option<(usize, usi
     })
 }
 
-pub fn find_triple_with_sum(inputs: &[usize], target: usize) -> Option<(usize, usize, usize)> {
+/// Find a pair summing to `target` in one pass, looking up each entry's
+/// complement among the entries before it
+pub fn find_pair_with_sum_hashed<T: Amount>(inputs: &[T], target: T) -> Option<(T, T)> {
+    let mut seen = HashSet::with_capacity(inputs.len());
+    for &x in inputs {
+        if let Some(complement) = target.checked_sub(x) {
+            if seen.contains(&complement) {
+                return Some((complement, x));
+            }
+        }
+        seen.insert(x);
+    }
+    None
+}
+
+/// Find a pair summing to `target` by sorting a copy of the entries and
+/// scanning inwards from both ends. The pair is returned in ascending order.
+pub fn find_pair_with_sum_sorted<T: Amount>(inputs: &[T], target: T) -> Option<(T, T)> {
+    let mut sorted = inputs.to_vec();
+    sorted.sort_unstable();
+    two_sum(&sorted, target)
+}
+
+/// The available implementations for finding a pair with a given sum.
+///
+/// Each only ever pairs entries at distinct positions, but may find a different
+/// pair when more than one matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PairStrategy {
+    /// Nested loops, O(n^2) ([`find_pair_with_sum_naive`])
+    Naive,
+    /// `tuple_combinations`, O(n^2) ([`find_pair_with_sum`])
+    Combinations,
+    /// Complement lookup, O(n) time and space ([`find_pair_with_sum_hashed`])
+    HashSet,
+    /// Sort and two-pointer scan, O(n log n) ([`find_pair_with_sum_sorted`])
+    TwoPointer,
+}
+
+impl PairStrategy {
+    pub const ALL: [PairStrategy; 4] = [
+        PairStrategy::Naive,
+        PairStrategy::Combinations,
+        PairStrategy::HashSet,
+        PairStrategy::TwoPointer,
+    ];
+
+    /// Pick a reasonable strategy for `len` entries: below a few dozen entries
+    /// the quadratic scan beats allocating anything
+    pub fn for_len(len: usize) -> Self {
+        if len < 32 {
+            PairStrategy::Naive
+        } else {
+            PairStrategy::HashSet
+        }
+    }
+
+    pub fn find<T: Amount>(self, inputs: &[T], target: T) -> Option<(T, T)> {
+        match self {
+            PairStrategy::Naive => find_pair_with_sum_naive(inputs, target),
+            PairStrategy::Combinations => find_pair_with_sum(inputs, target),
+            PairStrategy::HashSet => find_pair_with_sum_hashed(inputs, target),
+            PairStrategy::TwoPointer => find_pair_with_sum_sorted(inputs, target),
+        }
+    }
+}
+
+pub fn find_triple_with_sum<T: Amount>(inputs: &[T], target: T) -> Option<(T, T, T)> {
     inputs.iter().tuple_combinations().find_map(|(a, b, c)| {
-        if a + b + c == target {
+        if checked_sum3(*a, *b, *c) == Some(target) {
             Some((*a, *b, *c))
         } else {
             None
         }
     })
 }
+
+/// How to decide whether two matching tuples are the same
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Distinct {
+    /// Each combination of positions in the input is distinct, so repeated
+    /// values yield repeated tuples
+    ByIndex,
+    /// Each (sorted) combination of values is only yielded once
+    ByValue,
+}
+
+/// Iterate over every pair of entries summing to `target`.
+///
+/// [`Distinct::ByIndex`] yields pairs in input order as they're found, scanning
+/// every combination. [`Distinct::ByValue`] yields pairs in ascending order.
+pub fn pairs_with_sum<T: Amount>(
+    inputs: &[T],
+    target: T,
+    distinct: Distinct,
+) -> impl Iterator<Item = (T, T)> + '_ {
+    match distinct {
+        Distinct::ByIndex => Either::Left(
+            inputs
+                .iter()
+                .tuple_combinations()
+                .filter(move |(a, b)| a.checked_add(**b) == Some(target))
+                .map(|(a, b)| (*a, *b)),
+        ),
+        Distinct::ByValue => Either::Right(ValuePairs::new(inputs, target).map(|(pair, _)| pair)),
+    }
+}
+
+/// Iterate over every triple of entries summing to `target`, see
+/// [`pairs_with_sum`]
+pub fn triples_with_sum<T: Amount>(
+    inputs: &[T],
+    target: T,
+    distinct: Distinct,
+) -> impl Iterator<Item = (T, T, T)> + '_ {
+    match distinct {
+        Distinct::ByIndex => Either::Left(
+            inputs
+                .iter()
+                .tuple_combinations()
+                .filter(move |(a, b, c)| checked_sum3(**a, **b, **c) == Some(target))
+                .map(|(a, b, c)| (*a, *b, *c)),
+        ),
+        Distinct::ByValue => {
+            Either::Right(ValueTriples::new(inputs, target).map(|(triple, _)| triple))
+        }
+    }
+}
+
+/// Count the pairs [`pairs_with_sum`] would yield, without producing them
+pub fn count_pairs_with_sum<T: Amount>(inputs: &[T], target: T, distinct: Distinct) -> usize {
+    ValuePairs::new(inputs, target)
+        .map(|(_, ways)| match distinct {
+            Distinct::ByIndex => ways,
+            Distinct::ByValue => 1,
+        })
+        .sum()
+}
+
+/// Count the triples [`triples_with_sum`] would yield, without producing them
+pub fn count_triples_with_sum<T: Amount>(inputs: &[T], target: T, distinct: Distinct) -> usize {
+    ValueTriples::new(inputs, target)
+        .map(|(_, ways)| match distinct {
+            Distinct::ByIndex => ways,
+            Distinct::ByValue => 1,
+        })
+        .sum()
+}
+
+/// Distinct values in ascending order, with the number of times they appear
+fn value_counts<T: Amount>(inputs: &[T]) -> Vec<(T, usize)> {
+    inputs
+        .iter()
+        .fold(BTreeMap::new(), |mut counts, &x| {
+            *counts.entry(x).or_insert(0) += 1;
+            counts
+        })
+        .into_iter()
+        .collect()
+}
+
+/// The number of ways to choose `k` of `n` items
+fn choose(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1, |ways, i| ways * (n - i) / (i + 1))
+}
+
+/// Ascending pairs of values summing to a target, along with the number of
+/// combinations of positions they can be taken from
+struct ValuePairs<T> {
+    counts: Vec<(T, usize)>,
+    target: T,
+    i: usize,
+}
+
+impl<T: Amount> ValuePairs<T> {
+    fn new(inputs: &[T], target: T) -> Self {
+        Self {
+            counts: value_counts(inputs),
+            target,
+            i: 0,
+        }
+    }
+
+    fn count_of(&self, value: T) -> usize {
+        self.counts
+            .binary_search_by_key(&value, |&(v, _)| v)
+            .map_or(0, |i| self.counts[i].1)
+    }
+}
+
+impl<T: Amount> Iterator for ValuePairs<T> {
+    type Item = ((T, T), usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&(a, na)) = self.counts.get(self.i) {
+            self.i += 1;
+
+            let b = match self.target.checked_sub(a) {
+                Some(b) => b,
+                // `b` would be above the range of `T`, but may not be for a
+                // larger `a`
+                None if a < T::ZERO => continue,
+                // `b` would be below the range of `T`, and only gets smaller
+                None => return None,
+            };
+
+            if b < a {
+                // Every pair from here on has already been seen
+                return None;
+            }
+
+            let ways = if a == b {
+                choose(na, 2)
+            } else {
+                na * self.count_of(b)
+            };
+
+            if ways > 0 {
+                return Some(((a, b), ways));
+            }
+        }
+        None
+    }
+}
+
+/// Ascending triples of values summing to a target, see [`ValuePairs`]
+struct ValueTriples<T> {
+    pairs: ValuePairs<T>,
+    j: usize,
+}
+
+impl<T: Amount> ValueTriples<T> {
+    fn new(inputs: &[T], target: T) -> Self {
+        Self {
+            pairs: ValuePairs::new(inputs, target),
+            j: 0,
+        }
+    }
+}
+
+impl<T: Amount> Iterator for ValueTriples<T> {
+    type Item = ((T, T, T), usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        let counts = &self.pairs.counts;
+        while let Some(&(a, na)) = counts.get(self.pairs.i) {
+            while let Some(&(b, nb)) = counts.get(self.j) {
+                self.j += 1;
+
+                let c = match checked_rest(self.pairs.target, a, b) {
+                    Ok(c) if c >= b => c,
+                    // Larger `b` only make `c` smaller
+                    Ok(_) => break,
+                    // Too large, but a larger `b` may bring `c` back into range
+                    Err(Overflow::AboveMax) => continue,
+                    Err(Overflow::BelowMin) => break,
+                };
+
+                let ways = match (a == b, b == c) {
+                    (true, true) => choose(na, 3),
+                    (true, false) => choose(na, 2) * self.pairs.count_of(c),
+                    (false, true) => na * choose(nb, 2),
+                    (false, false) => na * nb * self.pairs.count_of(c),
+                };
+
+                if ways > 0 {
+                    return Some(((a, b, c), ways));
+                }
+            }
+
+            self.pairs.i += 1;
+            self.j = self.pairs.i;
+        }
+        None
+    }
+}
+
+/// Find `k` entries (at distinct positions in `inputs`) that sum to `target`,
+/// returned in ascending order.
+///
+/// The entries are sorted first, so pairs can be found with a two-pointer scan
+/// and larger tuples can skip partial sums that are already out of reach.
+/// Quadruples meet in the middle over pair sums in O(n^2), and quintuples fix
+/// their middle entry and scan the sorted pair sums either side of it in
+/// O(n^3), so the search is O(n^2) for `k <= 4` and O(n^(k - 2)) beyond that.
+pub fn find_k_with_sum<T: Amount>(inputs: &[T], k: usize, target: T) -> Option<Vec<T>> {
+    if k > inputs.len() {
+        return None;
+    }
+
+    let mut sorted = inputs.to_vec();
+    sorted.sort_unstable();
+
+    let mut found = Vec::with_capacity(k);
+    if k_sum(&sorted, k, target, &mut found) {
+        found.sort_unstable();
+        Some(found)
+    } else {
+        None
+    }
+}
+
+/// Recursive step of [`find_k_with_sum`], pushing matches onto `found`.
+///
+/// `sorted` must contain at least `k` entries.
+fn k_sum<T: Amount>(sorted: &[T], k: usize, target: T, found: &mut Vec<T>) -> bool {
+    let n = sorted.len();
+    match k {
+        0 => target == T::ZERO,
+        1 => {
+            let hit = sorted.binary_search(&target).is_ok();
+            if hit {
+                found.push(target);
+            }
+            hit
+        }
+        2 => two_sum(sorted, target)
+            .map(|(a, b)| found.extend([a, b]))
+            .is_some(),
+        // Meeting in the middle needs every pair sum to fit in `T`
+        4 if pair_sums_fit(sorted) => four_sum(sorted, target)
+            .map(|quad| found.extend(quad))
+            .is_some(),
+        5 if pair_sums_fit(sorted) => five_sum(sorted, target)
+            .map(|quint| found.extend(quint))
+            .is_some(),
+        // Fix the entry with the same sign as `target` that's furthest from
+        // zero, which keeps `target - entry` in range for any real match
+        _ if target < T::ZERO => {
+            for i in 0..=(n - k) {
+                if i > 0 && sorted[i] == sorted[i - 1] {
+                    // Already tried this value in this position
+                    continue;
+                }
+
+                if let Some(min) = checked_sum(&sorted[i..(i + k)]) {
+                    if min > target {
+                        // Every later start only gets larger
+                        break;
+                    }
+                }
+                if let Some(max) =
+                    checked_sum(&sorted[(n - k + 1)..]).and_then(|rest| rest.checked_add(sorted[i]))
+                {
+                    if max < target {
+                        continue;
+                    }
+                }
+
+                if let Some(rest) = target.checked_sub(sorted[i]) {
+                    found.push(sorted[i]);
+                    if k_sum(&sorted[(i + 1)..], k - 1, rest, found) {
+                        return true;
+                    }
+                    found.pop();
+                }
+            }
+            false
+        }
+        _ => {
+            for i in ((k - 1)..n).rev() {
+                if i + 1 < n && sorted[i] == sorted[i + 1] {
+                    // Already tried this value in this position
+                    continue;
+                }
+
+                if let Some(max) = checked_sum(&sorted[(i + 1 - k)..=i]) {
+                    if max < target {
+                        // Every earlier end only gets smaller
+                        break;
+                    }
+                }
+                if let Some(min) =
+                    checked_sum(&sorted[..(k - 1)]).and_then(|rest| rest.checked_add(sorted[i]))
+                {
+                    if min > target {
+                        continue;
+                    }
+                }
+
+                if let Some(rest) = target.checked_sub(sorted[i]) {
+                    found.push(sorted[i]);
+                    if k_sum(&sorted[..i], k - 1, rest, found) {
+                        return true;
+                    }
+                    found.pop();
+                }
+            }
+            false
+        }
+    }
+}
+
+/// Find a pair in `sorted` summing to `target` with a two-pointer scan
+fn two_sum<T: Amount>(sorted: &[T], target: T) -> Option<(T, T)> {
+    if sorted.len() < 2 {
+        return None;
+    }
+
+    let (mut i, mut j) = (0, sorted.len() - 1);
+    while i < j {
+        match sorted[i].checked_add(sorted[j]).map(|sum| sum.cmp(&target)) {
+            Some(std::cmp::Ordering::Equal) => return Some((sorted[i], sorted[j])),
+            Some(std::cmp::Ordering::Less) => i += 1,
+            Some(std::cmp::Ordering::Greater) => j -= 1,
+            // Overflow means both share a sign: too far above or below
+            None if sorted[j] > T::ZERO => j -= 1,
+            None => i += 1,
+        }
+    }
+    None
+}
+
+/// Whether every pair of entries in `sorted` can be summed without overflowing
+fn pair_sums_fit<T: Amount>(sorted: &[T]) -> bool {
+    let n = sorted.len();
+    sorted[0].checked_add(sorted[1]).is_some() && sorted[n - 2].checked_add(sorted[n - 1]).is_some()
+}
+
+/// Find four entries in `sorted` summing to `target` by matching each pair
+/// `(i, j)` against the sums of pairs entirely before `i`.
+///
+/// Every pair sum must fit in `T`.
+fn four_sum<T: Amount>(sorted: &[T], target: T) -> Option<[T; 4]> {
+    let mut pairs = HashMap::new();
+    for i in 0..sorted.len() {
+        for j in (i + 1)..sorted.len() {
+            let rest = sorted[i]
+                .checked_add(sorted[j])
+                .and_then(|sum| target.checked_sub(sum));
+            if let Some(&(a, b)) = rest.and_then(|rest| pairs.get(&rest)) {
+                return Some([a, b, sorted[i], sorted[j]]);
+            }
+        }
+
+        for &a in &sorted[..i] {
+            if let Some(sum) = a.checked_add(sorted[i]) {
+                pairs.entry(sum).or_insert((a, sorted[i]));
+            }
+        }
+    }
+    None
+}
+
+/// Find five entries in `sorted` summing to `target` by fixing each middle
+/// entry in turn, and scanning the sorted sums of pairs before it against those
+/// after it with two pointers.
+///
+/// The pair sums are updated incrementally as the middle entry moves, so each
+/// step is linear in the number of pairs. Every pair sum must fit in `T`.
+fn five_sum<T: Amount>(sorted: &[T], target: T) -> Option<[T; 5]> {
+    let n = sorted.len();
+    let pair = |i: usize, j: usize| sorted[i].checked_add(sorted[j]).expect("pair sums fit");
+
+    // Pair sums entirely before and entirely after the middle entry
+    let mut before = vec![pair(0, 1)];
+    let mut after = (3..n)
+        .flat_map(|i| ((i + 1)..n).map(move |j| (i, j)))
+        .map(|(i, j)| pair(i, j))
+        .collect::<Vec<_>>();
+    after.sort_unstable();
+    let mut scratch = Vec::new();
+
+    for mid in 2..(n - 2) {
+        if mid > 2 {
+            // Pairs ending at the last middle entry are now before this one,
+            // and pairs starting here are no longer after it
+            merge_sorted(
+                &mut before,
+                (0..(mid - 1)).map(|i| pair(i, mid - 1)),
+                &mut scratch,
+            );
+            remove_sorted(
+                &mut after,
+                ((mid + 1)..n).map(|j| pair(mid, j)),
+                &mut scratch,
+            );
+        }
+
+        let mut r = after.len();
+        for (i, &lower) in before.iter().enumerate() {
+            if i > 0 && lower == before[i - 1] {
+                continue;
+            }
+
+            // The upper pair needed only gets smaller as `lower` grows
+            let upper = match checked_rest(target, sorted[mid], lower) {
+                Ok(upper) => upper,
+                Err(Overflow::AboveMax) => continue,
+                Err(Overflow::BelowMin) => break,
+            };
+            while r > 0 && after[r - 1] > upper {
+                r -= 1;
+            }
+            if r == 0 {
+                break;
+            }
+
+            if after[r - 1] == upper {
+                let (a, b) = two_sum(&sorted[..mid], lower)?;
+                let (d, e) = two_sum(&sorted[(mid + 1)..], upper)?;
+                return Some([a, b, sorted[mid], d, e]);
+            }
+        }
+    }
+    None
+}
+
+/// Merge the ascending `values` into the sorted `into`
+fn merge_sorted<T: Ord + Copy>(
+    into: &mut Vec<T>,
+    values: impl Iterator<Item = T>,
+    scratch: &mut Vec<T>,
+) {
+    scratch.clear();
+    scratch.extend(into.iter().copied().merge(values));
+    std::mem::swap(into, scratch);
+}
+
+/// Remove one occurrence of each of the ascending `values` from the sorted
+/// `from`, all of which must be present
+fn remove_sorted<T: Ord + Copy>(
+    from: &mut Vec<T>,
+    values: impl Iterator<Item = T>,
+    scratch: &mut Vec<T>,
+) {
+    let mut values = values.peekable();
+    scratch.clear();
+    for &x in from.iter() {
+        if values.next_if_eq(&x).is_none() {
+            scratch.push(x);
+        }
+    }
+    std::mem::swap(from, scratch);
+}
+
+/// Bounds on the dynamic programming tables used for subset sums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubsetSumLimits {
+    /// The largest target that will be searched for
+    pub max_target: usize,
+    /// The most memory a table may take up
+    pub max_table_bytes: usize,
+}
+
+impl Default for SubsetSumLimits {
+    fn default() -> Self {
+        Self {
+            max_target: 1 << 20,
+            max_table_bytes: 64 << 20,
+        }
+    }
+}
+
+impl SubsetSumLimits {
+    /// Check `target`, and a table of `cells` entries of `T`, against the limits
+    fn check<T>(&self, target: usize, cells: Option<usize>) -> Result<(), SubsetSumError> {
+        if target > self.max_target {
+            return Err(SubsetSumError::TargetTooLarge {
+                target,
+                limit: self.max_target,
+            });
+        }
+
+        let needed = cells
+            .and_then(|cells| cells.checked_mul(size_of::<T>()))
+            .unwrap_or(usize::MAX);
+        if needed > self.max_table_bytes {
+            return Err(SubsetSumError::TableTooLarge {
+                needed,
+                limit: self.max_table_bytes,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SubsetSumError {
+    #[error("Target {target} is larger than the limit of {limit}")]
+    TargetTooLarge { target: usize, limit: usize },
+
+    #[error("Subset sum table needs {needed} bytes, more than the limit of {limit}")]
+    TableTooLarge { needed: usize, limit: usize },
+}
+
+/// Find a subset of entries (of any size) summing to `target`, returned in
+/// input order.
+///
+/// This is the classic 0/1 knapsack table over every sum up to `target`, so it
+/// takes O(n * target) time and O(target) memory.
+pub fn find_subset_with_sum(
+    inputs: &[usize],
+    target: usize,
+    limits: SubsetSumLimits,
+) -> Result<Option<Vec<usize>>, SubsetSumError> {
+    limits.check::<usize>(target, target.checked_add(1))?;
+
+    // The index of the entry that first reached each sum, which can only have
+    // been reached from a smaller sum using entries before it
+    const NO_ENTRY: usize = usize::MAX;
+    let mut via = vec![NO_ENTRY; target + 1];
+
+    for (i, &x) in inputs.iter().enumerate() {
+        if x > target {
+            continue;
+        }
+        for sum in (x..=target).rev() {
+            if via[sum] == NO_ENTRY && (sum == x || via[sum - x] != NO_ENTRY) {
+                via[sum] = i;
+            }
+        }
+        if via[target] != NO_ENTRY {
+            break;
+        }
+    }
+
+    if target == 0 {
+        return Ok(Some(Vec::new()));
+    } else if via[target] == NO_ENTRY {
+        return Ok(None);
+    }
+
+    let mut subset = Vec::new();
+    let mut sum = target;
+    while sum > 0 {
+        let x = inputs[via[sum]];
+        subset.push(x);
+        sum -= x;
+    }
+    subset.reverse();
+    Ok(Some(subset))
+}
+
+/// Find every subset summing to `target` with the fewest possible entries.
+///
+/// Subsets are distinct by value, each sorted in ascending order. The table of
+/// fewest entries needed for each sum from each prefix of the distinct values
+/// takes O(n * target) time and O(distinct * target) memory, and is then
+/// walked back in time proportional to the number of subsets found.
+pub fn minimal_subsets_with_sum(
+    inputs: &[usize],
+    target: usize,
+    limits: SubsetSumLimits,
+) -> Result<Vec<Vec<usize>>, SubsetSumError> {
+    let counts = value_counts(inputs);
+    let width = target.checked_add(1);
+    limits.check::<u32>(target, width.and_then(|w| w.checked_mul(counts.len() + 1)))?;
+
+    // fewest[i][sum]: the fewest entries with the first `i` distinct values
+    // that sum to `sum`
+    let width = target + 1;
+    let mut fewest = vec![UNREACHED; width * (counts.len() + 1)];
+    fewest[0] = 0;
+
+    for (i, &(x, n)) in counts.iter().enumerate() {
+        let (before, row) = fewest.split_at_mut((i + 1) * width);
+        let prev = &before[(i * width)..];
+        let row = &mut row[..width];
+
+        for (sum, cell) in row.iter_mut().enumerate() {
+            *cell = copies(x, n, sum)
+                .filter(|&c| prev[sum - c * x] != UNREACHED)
+                .map(|c| prev[sum - c * x] + c as u32)
+                .min()
+                .unwrap_or(UNREACHED);
+        }
+    }
+
+    let mut subsets = Vec::new();
+    if fewest[counts.len() * width + target] != UNREACHED {
+        collect_minimal(
+            &counts,
+            &fewest,
+            width,
+            counts.len(),
+            target,
+            &mut Vec::new(),
+            &mut subsets,
+        );
+    }
+    subsets.sort_unstable();
+    Ok(subsets)
+}
+
+/// Marks a sum in the [`minimal_subsets_with_sum`] table that can't be reached
+const UNREACHED: u32 = u32::MAX;
+
+/// The numbers of copies of `x` (of `n` available) that could be part of `sum`
+fn copies(x: usize, n: usize, sum: usize) -> std::ops::RangeInclusive<usize> {
+    // Zeros never help reach a sum with fewer entries
+    let most = sum.checked_div(x).map_or(0, |most| most.min(n));
+    0..=most
+}
+
+/// Walk back through the table from [`minimal_subsets_with_sum`], following
+/// every number of copies of each value that keeps the count minimal.
+///
+/// Each branch taken leads to at least one subset, and different branches to
+/// different subsets, so nothing is visited without producing a result.
+fn collect_minimal(
+    counts: &[(usize, usize)],
+    fewest: &[u32],
+    width: usize,
+    i: usize,
+    sum: usize,
+    current: &mut Vec<usize>,
+    subsets: &mut Vec<Vec<usize>>,
+) {
+    if i == 0 {
+        // Only a sum of zero is reachable with no values
+        let mut subset = current.clone();
+        subset.reverse();
+        subsets.push(subset);
+        return;
+    }
+
+    let count = fewest[i * width + sum];
+    let prev = (i - 1) * width;
+    let (x, n) = counts[i - 1];
+
+    for c in copies(x, n, sum) {
+        let rest = fewest[prev + sum - c * x];
+        if rest != UNREACHED && rest + c as u32 == count {
+            current.extend(std::iter::repeat_n(x, c));
+            collect_minimal(counts, fewest, width, i - 1, sum - c * x, current, subsets);
+            current.truncate(current.len() - c);
+        }
+    }
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Parsed<'a> = Vec<usize>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type Error = SolveError;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Self::Error> {
+        Ok(parse_entries(input)?)
+    }
+
+    fn part1(inputs: &Self::Parsed<'_>) -> Result<Self::Answer1, Self::Error> {
+        let (x, y) = find_pair_with_sum(inputs, TARGET_SUM).ok_or(SolveError::NoPair)?;
+        Ok(x * y)
+    }
+
+    fn part2(inputs: &Self::Parsed<'_>) -> Result<Self::Answer2, Self::Error> {
+        let (x, y, z) = find_triple_with_sum(inputs, TARGET_SUM).ok_or(SolveError::NoTriple)?;
+        Ok(x * y * z)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SolveError {
+    #[error("{0}")]
+    Parse(#[from] ParseError),
+
+    #[error("No pair of entries sums to the target")]
+    NoPair,
+
+    #[error("No triple of entries sums to the target")]
+    NoTriple,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_ENTRIES: [usize; 6] = [1721, 979, 366, 299, 675, 1456];
+
+    #[test]
+    fn test_parse() {
+        let input = "1721\n979  # trailing comment\n\n# 1000\n  366\r\n-299\n";
+        assert_eq!(parse_entries::<i32>(input), Ok(vec![1721, 979, 366, -299]));
+
+        let err = parse_entries::<u64>(input).expect_err("Parsed a negative u64");
+        assert_eq!((err.line, err.text.as_str()), (6, "-299"));
+
+        let err = parse_entries::<usize>("1\n2\n3O\n").expect_err("Parsed a typo");
+        assert_eq!(err.line, 3);
+        assert_eq!(
+            err.to_string(),
+            "Invalid entry on line 3 \"3O\": invalid digit found in string"
+        );
+
+        assert_eq!(parse_entries::<usize>(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_pair_strategies() {
+        for strategy in PairStrategy::ALL {
+            let (x, y) = strategy
+                .find(&TEST_ENTRIES, TARGET_SUM)
+                .expect("No pair found");
+            assert_eq!(x * y, 514579, "{:?}", strategy);
+
+            // 1010 needs to appear twice to pair with itself
+            assert_eq!(strategy.find(&[1, 1010, 7], TARGET_SUM), None);
+            assert_eq!(
+                strategy.find(&[1, 1010, 7, 1010], TARGET_SUM),
+                Some((1010, 1010))
+            );
+            assert_eq!(strategy.find(&[], TARGET_SUM), None);
+            assert_eq!(strategy.find(&[2020], TARGET_SUM), None);
+            assert_eq!(
+                strategy.find(&[2020, 0], TARGET_SUM).map(|(x, y)| x + y),
+                Some(2020)
+            );
+        }
+    }
+
+    #[test]
+    fn test_all_pairs() {
+        let entries = [1010, 1, 2019, 1010, 1, 1010, 2020];
+        assert_eq!(
+            pairs_with_sum(&entries, TARGET_SUM, Distinct::ByIndex).collect::<Vec<_>>(),
+            [
+                (1010, 1010),
+                (1010, 1010),
+                (1, 2019),
+                (2019, 1),
+                (1010, 1010)
+            ]
+        );
+        assert_eq!(
+            pairs_with_sum(&entries, TARGET_SUM, Distinct::ByValue).collect::<Vec<_>>(),
+            [(1, 2019), (1010, 1010)]
+        );
+        assert_eq!(
+            count_pairs_with_sum(&entries, TARGET_SUM, Distinct::ByIndex),
+            5
+        );
+        assert_eq!(
+            count_pairs_with_sum(&entries, TARGET_SUM, Distinct::ByValue),
+            2
+        );
+        assert_eq!(count_pairs_with_sum(&entries, 1, Distinct::ByIndex), 0);
+    }
+
+    #[test]
+    fn test_all_triples() {
+        let entries = [10, 5, 5, 5, 0, 10, 15];
+        assert_eq!(
+            triples_with_sum(&entries, 15, Distinct::ByValue).collect::<Vec<_>>(),
+            [(0, 5, 10), (5, 5, 5)]
+        );
+        assert_eq!(count_triples_with_sum(&entries, 15, Distinct::ByIndex), 7);
+        assert_eq!(
+            triples_with_sum(&entries, 15, Distinct::ByIndex).count(),
+            count_triples_with_sum(&entries, 15, Distinct::ByIndex)
+        );
+        assert_eq!(count_triples_with_sum(&entries, 15, Distinct::ByValue), 2);
+
+        let entries = (0..30).map(|i| (i * 37) % 41).collect::<Vec<_>>();
+        for target in 0..130 {
+            let by_index = triples_with_sum(&entries, target, Distinct::ByIndex)
+                .map(|(a, b, c)| {
+                    let mut t = [a, b, c];
+                    t.sort_unstable();
+                    t
+                })
+                .collect::<Vec<_>>();
+            let by_value = triples_with_sum(&entries, target, Distinct::ByValue)
+                .map(|(a, b, c)| [a, b, c])
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                by_index
+                    .iter()
+                    .cloned()
+                    .sorted()
+                    .dedup()
+                    .collect::<Vec<_>>(),
+                by_value
+            );
+            assert_eq!(
+                count_triples_with_sum(&entries, target, Distinct::ByIndex),
+                by_index.len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_signed() {
+        let refunds = [-50i64, 2070, 1000, 1020, -30, 1050];
+        for strategy in PairStrategy::ALL {
+            let (x, y) = strategy.find(&refunds, 2020).expect("No pair found");
+            assert_eq!(x + y, 2020, "{:?}", strategy);
+        }
+        assert_eq!(
+            find_triple_with_sum(&refunds, 2020),
+            Some((-50, 1020, 1050))
+        );
+        assert_eq!(
+            find_k_with_sum(&refunds, 3, 2020),
+            Some(vec![-50, 1020, 1050])
+        );
+        assert_eq!(
+            triples_with_sum(&refunds, 2020, Distinct::ByValue).collect::<Vec<_>>(),
+            [(-50, 1020, 1050), (-30, 1000, 1050)]
+        );
+        assert_eq!(count_pairs_with_sum(&refunds, 2020, Distinct::ByIndex), 2);
+        assert_eq!(find_k_with_sum(&refunds, 2, -80), Some(vec![-50, -30]));
+    }
+
+    #[test]
+    fn test_overflow_is_never_a_match() {
+        // 100 + 100 wraps around to -56 in an i8
+        for strategy in PairStrategy::ALL {
+            assert_eq!(strategy.find(&[100i8, 100], -56), None, "{:?}", strategy);
+        }
+        assert_eq!(find_k_with_sum(&[100i8, 100], 2, -56), None);
+        assert_eq!(
+            count_pairs_with_sum(&[100i8, 100], -56, Distinct::ByIndex),
+            0
+        );
+        assert_eq!(find_triple_with_sum(&[100i8, 100, 100], 44), None);
+        assert_eq!(find_k_with_sum(&[100i8, 100, 100], 3, 44), None);
+        assert_eq!(find_k_with_sum(&[u8::MAX, 1, 1, 1], 4, 2), None);
+    }
+
+    #[test]
+    fn test_overflowing_partial_sums() {
+        // The total fits, even though some partial sums don't
+        assert_eq!(
+            find_triple_with_sum(&[100i8, 100, -100], 100),
+            Some((100, 100, -100))
+        );
+        assert_eq!(
+            find_k_with_sum(&[100i8, -100, 100], 3, 100),
+            Some(vec![-100, 100, 100])
+        );
+        assert_eq!(
+            find_k_with_sum(&[120i8, -120, 120, -119], 4, 1),
+            Some(vec![-120, -119, 120, 120])
+        );
+        assert_eq!(
+            find_k_with_sum(&[-120i8, -120, 120, -119, 5, 120], 5, -119),
+            Some(vec![-120, -120, -119, 120, 120])
+        );
+        assert_eq!(
+            triples_with_sum(&[-100i8, 100, 100], 100, Distinct::ByValue).collect::<Vec<_>>(),
+            [(-100, 100, 100)]
+        );
+        assert_eq!(
+            pairs_with_sum(&[-100i8, 100, 27, 100], 127, Distinct::ByValue).collect::<Vec<_>>(),
+            [(27, 100)]
+        );
+        assert_eq!(
+            find_triple_with_sum(&[i128::MAX, i128::MIN, 1], 0),
+            Some((i128::MAX, i128::MIN, 1))
+        );
+        assert_eq!(
+            find_pair_with_sum(&[u64::MAX - 1, 5, 1], u64::MAX),
+            Some((u64::MAX - 1, 1))
+        );
+    }
+
+    #[test]
+    fn test_subset_sum() {
+        let limits = SubsetSumLimits::default();
+        let subset = find_subset_with_sum(&TEST_ENTRIES, TARGET_SUM, limits)
+            .expect("Within limits")
+            .expect("No subset found");
+        assert_eq!(subset.iter().sum::<usize>(), TARGET_SUM);
+
+        let subset = find_subset_with_sum(&TEST_ENTRIES, 299 + 366 + 675 + 979, limits)
+            .expect("Within limits")
+            .expect("No subset found");
+        assert_eq!(subset, [979, 366, 299, 675]);
+
+        assert_eq!(
+            find_subset_with_sum(&TEST_ENTRIES, 0, limits),
+            Ok(Some(vec![]))
+        );
+        assert_eq!(find_subset_with_sum(&TEST_ENTRIES, 1, limits), Ok(None));
+        assert_eq!(find_subset_with_sum(&[1010], TARGET_SUM, limits), Ok(None));
+        assert_eq!(
+            find_subset_with_sum(&[1010, 1010], TARGET_SUM, limits),
+            Ok(Some(vec![1010, 1010]))
+        );
+    }
+
+    #[test]
+    fn test_minimal_subsets() {
+        let limits = SubsetSumLimits::default();
+        assert_eq!(
+            minimal_subsets_with_sum(&TEST_ENTRIES, TARGET_SUM, limits),
+            Ok(vec![vec![299, 1721]])
+        );
+        assert_eq!(
+            minimal_subsets_with_sum(&[1, 2, 3, 4, 5, 3], 8, limits),
+            Ok(vec![vec![3, 5]])
+        );
+        assert_eq!(
+            minimal_subsets_with_sum(&[1, 2, 3, 4, 5, 6], 10, limits),
+            Ok(vec![vec![4, 6]])
+        );
+        assert_eq!(
+            minimal_subsets_with_sum(&[1, 2, 3, 4, 5, 6], 12, limits),
+            Ok(vec![vec![1, 5, 6], vec![2, 4, 6], vec![3, 4, 5]])
+        );
+        assert_eq!(minimal_subsets_with_sum(&[2, 4], 5, limits), Ok(vec![]));
+        assert_eq!(
+            minimal_subsets_with_sum(&[0, 3], 0, limits),
+            Ok(vec![vec![]])
+        );
+        assert_eq!(
+            minimal_subsets_with_sum(&[0, 2, 2, 2, 3, 3, 4], 7, limits),
+            Ok(vec![vec![3, 4]])
+        );
+        assert_eq!(
+            minimal_subsets_with_sum(&[2, 2, 2, 2, 3, 3, 1], 8, limits),
+            Ok(vec![vec![2, 3, 3]])
+        );
+        assert_eq!(
+            minimal_subsets_with_sum(&[2, 2, 2, 1, 1, 4], 6, limits),
+            Ok(vec![vec![2, 4]])
+        );
+
+        // Repeated values don't multiply the work of finding one subset
+        assert_eq!(
+            minimal_subsets_with_sum(&[1; 64], 32, limits),
+            Ok(vec![vec![1; 32]])
+        );
+    }
+
+    #[test]
+    fn test_subset_sum_limits() {
+        let limits = SubsetSumLimits {
+            max_target: 100,
+            max_table_bytes: 1024,
+        };
+        assert_eq!(
+            find_subset_with_sum(&TEST_ENTRIES, TARGET_SUM, limits),
+            Err(SubsetSumError::TargetTooLarge {
+                target: TARGET_SUM,
+                limit: 100
+            })
+        );
+        assert_eq!(
+            minimal_subsets_with_sum(&[1, 2, 3, 4, 5, 6, 7, 8, 9], 99, limits),
+            Err(SubsetSumError::TableTooLarge {
+                needed: 10 * 100 * 4,
+                limit: 1024
+            })
+        );
+        // Only distinct values take up space in the table
+        assert!(minimal_subsets_with_sum(&[1; 9], 99, limits).is_ok());
+        assert!(minimal_subsets_with_sum(&[1, 2, 3, 4, 5, 6, 7, 8, 9], 9, limits).is_ok());
+    }
+
+    #[test]
+    fn test_k_sum() {
+        assert_eq!(find_k_with_sum(&TEST_ENTRIES, 0, 0), Some(vec![]));
+        assert_eq!(find_k_with_sum(&TEST_ENTRIES, 1, 366), Some(vec![366]));
+        assert_eq!(
+            find_k_with_sum(&TEST_ENTRIES, 2, TARGET_SUM),
+            Some(vec![299, 1721])
+        );
+        assert_eq!(
+            find_k_with_sum(&TEST_ENTRIES, 3, TARGET_SUM),
+            Some(vec![366, 675, 979])
+        );
+        assert_eq!(
+            find_k_with_sum(&TEST_ENTRIES, 4, 299 + 366 + 675 + 1456),
+            Some(vec![299, 366, 675, 1456])
+        );
+        assert_eq!(
+            find_k_with_sum(&TEST_ENTRIES, 5, 299 + 366 + 979 + 1456 + 1721),
+            Some(vec![299, 366, 979, 1456, 1721])
+        );
+        assert_eq!(find_k_with_sum(&TEST_ENTRIES, 7, TARGET_SUM), None);
+        assert_eq!(find_k_with_sum(&TEST_ENTRIES, 4, TARGET_SUM), None);
+    }
+
+    #[test]
+    fn test_k_sum_distinct_positions() {
+        // 1010 only appears once, so can't be used twice
+        assert_eq!(find_k_with_sum(&[1010, 5, 15], 2, TARGET_SUM), None);
+        assert_eq!(
+            find_k_with_sum(&[1010, 5, 1010], 2, TARGET_SUM),
+            Some(vec![1010, 1010])
+        );
+        assert_eq!(find_k_with_sum(&[505, 505, 505, 1], 4, TARGET_SUM), None);
+        assert_eq!(
+            find_k_with_sum(&[505, 505, 505, 505], 4, TARGET_SUM),
+            Some(vec![505; 4])
+        );
+    }
+
+    #[test]
+    fn test_signed_tuples_match_combinations() {
+        assert_eq!(
+            triples_with_sum(&[-100i8, 50, 100, 100], 100, Distinct::ByValue).collect::<Vec<_>>(),
+            [(-100, 100, 100)]
+        );
+
+        let entries = (0..14)
+            .map(|i: i32| ((i * 97 + 31) % 256 - 128) as i8)
+            .chain([-100, 50, 100, 100, 127, -128])
+            .collect::<Vec<_>>();
+        for target in i8::MIN..=i8::MAX {
+            let by_index = pairs_with_sum(&entries, target, Distinct::ByIndex)
+                .map(|(a, b)| (a.min(b), a.max(b)))
+                .collect::<Vec<_>>();
+            let by_value = pairs_with_sum(&entries, target, Distinct::ByValue).collect::<Vec<_>>();
+            assert_eq!(
+                by_value,
+                by_index
+                    .iter()
+                    .copied()
+                    .sorted()
+                    .dedup()
+                    .collect::<Vec<_>>(),
+                "target = {}",
+                target
+            );
+            assert_eq!(
+                count_pairs_with_sum(&entries, target, Distinct::ByIndex),
+                by_index.len()
+            );
+            assert_eq!(
+                count_pairs_with_sum(&entries, target, Distinct::ByValue),
+                by_value.len()
+            );
+
+            let by_index = triples_with_sum(&entries, target, Distinct::ByIndex)
+                .map(|(a, b, c)| {
+                    let mut triple = [a, b, c];
+                    triple.sort_unstable();
+                    (triple[0], triple[1], triple[2])
+                })
+                .collect::<Vec<_>>();
+            let by_value =
+                triples_with_sum(&entries, target, Distinct::ByValue).collect::<Vec<_>>();
+            assert_eq!(
+                by_value,
+                by_index
+                    .iter()
+                    .copied()
+                    .sorted()
+                    .dedup()
+                    .collect::<Vec<_>>(),
+                "target = {}",
+                target
+            );
+            assert_eq!(
+                count_triples_with_sum(&entries, target, Distinct::ByIndex),
+                by_index.len(),
+                "target = {}",
+                target
+            );
+            assert_eq!(
+                count_triples_with_sum(&entries, target, Distinct::ByValue),
+                by_value.len(),
+                "target = {}",
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn test_signed_k_sum_matches_combinations() {
+        let entries = (0..20)
+            .map(|i: i32| ((i * 97) % 256 - 128) as i8)
+            .collect::<Vec<_>>();
+        for k in 1..=5 {
+            let sums = entries
+                .iter()
+                .combinations(k)
+                .map(|c| c.into_iter().map(|&x| i32::from(x)).sum::<i32>())
+                .collect::<std::collections::HashSet<_>>();
+
+            for target in i8::MIN..=i8::MAX {
+                let found = find_k_with_sum(&entries, k, target);
+                assert_eq!(
+                    found.is_some(),
+                    sums.contains(&i32::from(target)),
+                    "k = {}, target = {}",
+                    k,
+                    target
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_k_sum_matches_combinations() {
+        let entries = (0..30).map(|i| (i * 37) % 101).collect::<Vec<_>>();
+        for k in 1..=5 {
+            let sums = entries
+                .iter()
+                .combinations(k)
+                .map(|c| c.into_iter().sum::<usize>())
+                .collect::<std::collections::HashSet<_>>();
+
+            for target in 0..300 {
+                let found = find_k_with_sum(&entries, k, target);
+                assert_eq!(
+                    found.is_some(),
+                    sums.contains(&target),
+                    "k = {}, target = {}",
+                    k,
+                    target
+                );
+                if let Some(found) = found {
+                    assert_eq!(found.len(), k);
+                    assert_eq!(found.iter().sum::<usize>(), target);
+                }
+            }
+        }
+    }
+}
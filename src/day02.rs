@@ -1,4 +1,15 @@
+use std::{collections::BTreeMap, fmt, io::BufRead, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    reader::{self, ReadError},
+    Solution,
+};
+
 /// An entry in the form: a-b x: abcdef
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Entry<'a> {
     letter: char,
     a: usize,
@@ -7,10 +18,86 @@ pub struct Entry<'a> {
     data: &'a str,
 }
 
-// TODO: FromStr isn't happy about lifetimes
-// impl<'a> FromStr for Entry<'a> {
-// type Err = ParseError;
-// fn from_str(s: &str) -> Result<Self, Self::Err> {
+/// An [`Entry`] that owns its password, for use beyond the input's lifetime.
+///
+/// Serializes as the entry's `a-b x: data` text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct EntryBuf {
+    letter: char,
+    a: usize,
+    b: usize,
+
+    data: String,
+}
+
+impl EntryBuf {
+    pub fn as_entry(&self) -> Entry<'_> {
+        Entry {
+            letter: self.letter,
+            a: self.a,
+            b: self.b,
+            data: &self.data,
+        }
+    }
+
+    /// Parse one entry per line of `reader`, holding only a line at a time
+    pub fn read_from(
+        reader: impl BufRead,
+    ) -> impl Iterator<Item = Result<EntryBuf, ReadError<ParseError>>> {
+        reader::parse_lines(reader, |line| Entry::parse(line).map(EntryBuf::from))
+    }
+}
+
+impl From<Entry<'_>> for EntryBuf {
+    fn from(entry: Entry<'_>) -> Self {
+        Self {
+            letter: entry.letter,
+            a: entry.a,
+            b: entry.b,
+            data: entry.data.to_string(),
+        }
+    }
+}
+
+impl<'a> From<&'a EntryBuf> for Entry<'a> {
+    fn from(entry: &'a EntryBuf) -> Self {
+        entry.as_entry()
+    }
+}
+
+impl FromStr for EntryBuf {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Entry::parse(s).map(Self::from)
+    }
+}
+
+impl TryFrom<String> for EntryBuf {
+    type Error = ParseError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<EntryBuf> for String {
+    fn from(entry: EntryBuf) -> Self {
+        entry.to_string()
+    }
+}
+
+impl fmt::Display for Entry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{} {}: {}", self.a, self.b, self.letter, self.data)
+    }
+}
+
+impl fmt::Display for EntryBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_entry().fmt(f)
+    }
+}
+
 impl<'a> TryFrom<&'a str> for Entry<'a> {
     type Error = ParseError;
     fn try_from(s: &'a str) -> Result<Self, Self::Error> {
@@ -54,6 +141,15 @@ pub enum ParseError {
 
     #[error("Failed to parse a/b as integers: {0}")]
     ParseInt(#[from] std::num::ParseIntError),
+
+    #[error("Expected {expected} at column {column}")]
+    Syntax {
+        column: usize,
+        expected: &'static str,
+    },
+
+    #[error("Unexpected trailing input at column {column}")]
+    Trailing { column: usize },
 }
 
 impl<'a> Entry<'a> {
@@ -63,33 +159,1125 @@ impl<'a> Entry<'a> {
         input.split('\n').map(Self::try_from)
     }
 
+    /// Parse a single entry, requiring the whole of `line` to be consumed
+    pub fn parse(line: &'a str) -> Result<Entry<'a>, ParseError> {
+        let column = |rest: &str| line[..(line.len() - rest.len())].chars().count() + 1;
+
+        match parser::entry(line) {
+            Ok(("", (a, b, letter, data))) => Ok(Entry { letter, a, b, data }),
+            Ok((rest, _)) => Err(ParseError::Trailing {
+                column: column(rest),
+            }),
+            Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+                // Report the innermost labelled step that failed
+                let (rest, expected) = e
+                    .errors
+                    .iter()
+                    .find_map(|(rest, kind)| match kind {
+                        nom::error::VerboseErrorKind::Context(expected) => Some((*rest, *expected)),
+                        _ => None,
+                    })
+                    .unwrap_or((line, "an entry"));
+                Err(ParseError::Syntax {
+                    column: column(rest),
+                    expected,
+                })
+            }
+            Err(nom::Err::Incomplete(_)) => unreachable!("complete parsers only"),
+        }
+    }
+
+    /// Parse one entry per line, allowing CRLF line endings and trailing
+    /// newlines at the end of the input
+    pub fn parse_iter_from(
+        input: &'a str,
+    ) -> impl Iterator<Item = Result<Entry<'a>, ParseError>> + Clone {
+        input
+            .trim_end_matches(['\r', '\n'])
+            .lines()
+            .map(Self::parse)
+    }
+
     /// Validate for part 1, where `a` is character min, `b` is character max
     pub fn validate_1(&self) -> bool {
-        let min = self.a;
-        let max = self.b;
-        let instances = self.data.chars().filter(|d| d == &self.letter).count();
-        (instances <= max) && (instances >= min)
+        self.validate_1_in(Mode::Chars).is_valid()
+    }
+
+    /// Validate for part 1, counting `letter` as units of `mode`. The policy is
+    /// malformed if `a` is greater than `b`
+    pub fn validate_1_in(&self, mode: Mode) -> Validity {
+        if self.a > self.b {
+            return Validity::Malformed(PolicyError::EmptyRange {
+                min: self.a,
+                max: self.b,
+            });
+        }
+        self.count_in(mode)
+            .map(|instances| (self.a..=self.b).contains(&instances))
+            .into()
     }
 
     /// Validate for part 2, where `a` and `b` are positions in `data` where the
     /// entry is valid if exactly one of those contains `letter`.
     ///
-    /// NOTE: `a` and `b` are 1-indexed
-    pub fn validate_2(&self) -> bool {
-        let a = self
-            .data
-            .chars()
-            .nth(self.a - 1)
-            .map(|c| c == self.letter)
-            .expect("a");
+    /// NOTE: `a` and `b` are 1-indexed, so a position of zero (or one past the
+    /// end of `data`) makes the policy itself malformed
+    pub fn validate_2(&self) -> Validity {
+        self.validate_2_in(Mode::Chars)
+    }
 
-        let b = self
-            .data
-            .chars()
-            .nth(self.b - 1)
-            .map(|c| c == self.letter)
-            .expect("b");
+    /// Validate for part 2, with positions counted in units of `mode`
+    pub fn validate_2_in(&self, mode: Mode) -> Validity {
+        self.letters_at(mode).map(|(a, b)| a ^ b).into()
+    }
+
+    /// The number of times `letter` appears in `data`
+    pub fn count_in(&self, mode: Mode) -> Result<usize, PolicyError> {
+        Ok(match mode {
+            Mode::Bytes => {
+                let letter = self.ascii_letter()?;
+                self.data.bytes().filter(|&b| b == letter).count()
+            }
+            Mode::Chars => self.data.chars().filter(|&c| c == self.letter).count(),
+            Mode::Graphemes => {
+                let mut buf = [0; 4];
+                let letter = &*self.letter.encode_utf8(&mut buf);
+                self.data.graphemes(true).filter(|&g| g == letter).count()
+            }
+        })
+    }
+
+    /// Whether positions `a` and `b` in `data` hold `letter`
+    fn letters_at(&self, mode: Mode) -> Result<(bool, bool), PolicyError> {
+        // Check for non-ASCII once, so byte positions can be indexed directly
+        if mode == Mode::Bytes {
+            self.ascii_letter()?;
+        }
+        Ok((self.letter_at(self.a, mode)?, self.letter_at(self.b, mode)?))
+    }
+
+    /// Whether the 1-indexed `position` in `data` holds `letter`.
+    ///
+    /// In byte mode, `letter` and `data` must already be known to be ASCII.
+    fn letter_at(&self, position: usize, mode: Mode) -> Result<bool, PolicyError> {
+        let index = position.checked_sub(1).ok_or(PolicyError::ZeroPosition)?;
+        let found = match mode {
+            Mode::Bytes => self
+                .data
+                .as_bytes()
+                .get(index)
+                .map(|&b| b == self.letter as u8),
+            Mode::Chars => self.data.chars().nth(index).map(|c| c == self.letter),
+            Mode::Graphemes => {
+                let mut buf = [0; 4];
+                let letter = &*self.letter.encode_utf8(&mut buf);
+                self.data.graphemes(true).nth(index).map(|g| g == letter)
+            }
+        };
+        found.ok_or_else(|| PolicyError::OutOfRange {
+            position,
+            len: mode.len(self.data),
+            mode,
+        })
+    }
+
+    /// `letter` as a byte, if both it and `data` are ASCII
+    fn ascii_letter(&self) -> Result<u8, PolicyError> {
+        match std::iter::once(self.letter)
+            .chain(self.data.chars())
+            .find(|c| !c.is_ascii())
+        {
+            Some(found) => Err(PolicyError::NonAscii { found }),
+            None => Ok(self.letter as u8),
+        }
+    }
+}
+
+/// What a position or count in a password is measured in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// ASCII bytes, indexed directly. Any non-ASCII input is malformed
+    Bytes,
+    /// Unicode scalar values (`char`s), as the puzzle was originally solved
+    #[default]
+    Chars,
+    /// Extended grapheme clusters, so e.g. `e` followed by a combining accent
+    /// is a single position that doesn't match `e`
+    Graphemes,
+}
+
+impl Mode {
+    /// The length of `s` in units of this mode
+    pub fn len(self, s: &str) -> usize {
+        match self {
+            Mode::Bytes => s.len(),
+            Mode::Chars => s.chars().count(),
+            Mode::Graphemes => s.graphemes(true).count(),
+        }
+    }
+
+    /// The name of a single unit of this mode
+    pub fn unit(self) -> &'static str {
+        match self {
+            Mode::Bytes => "byte",
+            Mode::Chars => "character",
+            Mode::Graphemes => "grapheme",
+        }
+    }
+
+    /// The suffix selecting this mode in a policy spec, empty for the default
+    fn spec_suffix(self) -> &'static str {
+        match self {
+            Mode::Bytes => "@bytes",
+            Mode::Chars => "",
+            Mode::Graphemes => "@graphemes",
+        }
+    }
+}
+
+impl FromStr for Mode {
+    type Err = PolicySpecError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bytes" => Ok(Mode::Bytes),
+            "chars" => Ok(Mode::Chars),
+            "graphemes" => Ok(Mode::Graphemes),
+            _ => Err(PolicySpecError::UnknownMode(s.to_string())),
+        }
+    }
+}
+
+/// The outcome of checking an entry against a policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Validity {
+    Valid,
+    Invalid,
+    /// The policy can't be applied to the entry's password
+    Malformed(PolicyError),
+}
+
+impl Validity {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Validity::Valid)
+    }
+}
+
+impl From<Result<bool, PolicyError>> for Validity {
+    fn from(result: Result<bool, PolicyError>) -> Self {
+        match result {
+            Ok(true) => Validity::Valid,
+            Ok(false) => Validity::Invalid,
+            Err(e) => Validity::Malformed(e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum PolicyError {
+    #[error("Position 0 is invalid, positions are 1-indexed")]
+    ZeroPosition,
+
+    #[error("Position {position} is past the end of the {len} {} password", .mode.unit())]
+    OutOfRange {
+        position: usize,
+        len: usize,
+        mode: Mode,
+    },
+
+    #[error("Range {min}-{max} is empty")]
+    EmptyRange { min: usize, max: usize },
+
+    #[error("Found non-ASCII character '{found}' in byte mode")]
+    NonAscii { found: char },
+}
+
+/// A rule a password can be audited against.
+///
+/// Policies display as the spec [`parse_policy`] selects them by. Each
+/// built-in policy measures the password in a [`Mode`].
+pub trait Policy: fmt::Display {
+    fn check(&self, entry: &Entry<'_>) -> Validity;
+}
+
+/// Part 1: `letter` appears between `a` and `b` times (inclusive)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CountRange {
+    pub mode: Mode,
+}
+
+/// Part 2: exactly one of positions `a` and `b` holds `letter`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExactlyOnePosition {
+    pub mode: Mode,
+}
+
+/// At least one of positions `a` and `b` holds `letter`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AtLeastOnePosition {
+    pub mode: Mode,
+}
+
+/// `letter` doesn't appear in the password at all
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ForbiddenLetter {
+    pub mode: Mode,
+}
+
+/// Every character of the password is in a class like `[a-z0-9]` or `[^xyz]`.
+///
+/// In grapheme mode only the first character of each grapheme is checked, so
+/// combining marks don't need to be in the class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharacterClass {
+    negated: bool,
+    ranges: Vec<(char, char)>,
+    pub mode: Mode,
+}
+
+/// Valid if every policy is valid
+pub struct All(pub Vec<Box<dyn Policy>>);
+
+/// Valid if any policy is valid
+pub struct Any(pub Vec<Box<dyn Policy>>);
+
+impl Policy for CountRange {
+    fn check(&self, entry: &Entry<'_>) -> Validity {
+        entry.validate_1_in(self.mode)
+    }
+}
+
+impl Policy for ExactlyOnePosition {
+    fn check(&self, entry: &Entry<'_>) -> Validity {
+        entry.validate_2_in(self.mode)
+    }
+}
+
+impl Policy for AtLeastOnePosition {
+    fn check(&self, entry: &Entry<'_>) -> Validity {
+        entry.letters_at(self.mode).map(|(a, b)| a | b).into()
+    }
+}
+
+impl Policy for ForbiddenLetter {
+    fn check(&self, entry: &Entry<'_>) -> Validity {
+        entry.count_in(self.mode).map(|count| count == 0).into()
+    }
+}
+
+impl CharacterClass {
+    pub fn contains(&self, c: char) -> bool {
+        self.ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != self.negated
+    }
+}
+
+impl Policy for CharacterClass {
+    fn check(&self, entry: &Entry<'_>) -> Validity {
+        match self.mode {
+            Mode::Bytes => entry
+                .ascii_letter()
+                .map(|_| entry.data.bytes().all(|b| self.contains(b as char)))
+                .into(),
+            Mode::Chars => Ok(entry.data.chars().all(|c| self.contains(c))).into(),
+            Mode::Graphemes => Ok(entry
+                .data
+                .graphemes(true)
+                .filter_map(|g| g.chars().next())
+                .all(|c| self.contains(c)))
+            .into(),
+        }
+    }
+}
+
+impl FromStr for CharacterClass {
+    type Err = PolicySpecError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || PolicySpecError::InvalidClass(s.to_string());
+        let inner = s
+            .strip_prefix('[')
+            .and_then(|s| s.strip_suffix(']'))
+            .ok_or_else(invalid)?;
+        let (negated, inner) = match inner.strip_prefix('^') {
+            Some(inner) => (true, inner),
+            None => (false, inner),
+        };
+
+        let mut ranges = Vec::new();
+        let mut chars = inner.chars().peekable();
+        while let Some(lo) = chars.next() {
+            if chars.next_if_eq(&'-').is_some() {
+                let hi = chars.next().ok_or_else(invalid)?;
+                if hi < lo {
+                    return Err(invalid());
+                }
+                ranges.push((lo, hi));
+            } else {
+                ranges.push((lo, lo));
+            }
+        }
+
+        if ranges.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            negated,
+            ranges,
+            mode: Mode::Chars,
+        })
+    }
+}
+
+impl Policy for All {
+    fn check(&self, entry: &Entry<'_>) -> Validity {
+        let mut validity = Validity::Valid;
+        for policy in &self.0 {
+            match policy.check(entry) {
+                Validity::Valid => {}
+                Validity::Invalid => validity = Validity::Invalid,
+                malformed => return malformed,
+            }
+        }
+        validity
+    }
+}
+
+impl Policy for Any {
+    fn check(&self, entry: &Entry<'_>) -> Validity {
+        let mut validity = Validity::Invalid;
+        for policy in &self.0 {
+            match policy.check(entry) {
+                Validity::Valid => return Validity::Valid,
+                Validity::Invalid => {}
+                malformed => validity = malformed,
+            }
+        }
+        validity
+    }
+}
+
+impl fmt::Display for CountRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "count{}", self.mode.spec_suffix())
+    }
+}
+
+impl fmt::Display for ExactlyOnePosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "exactly-one{}", self.mode.spec_suffix())
+    }
+}
+
+impl fmt::Display for AtLeastOnePosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at-least-one{}", self.mode.spec_suffix())
+    }
+}
+
+impl fmt::Display for ForbiddenLetter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "forbidden{}", self.mode.spec_suffix())
+    }
+}
+
+impl fmt::Display for CharacterClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "class:[{}", if self.negated { "^" } else { "" })?;
+        for &(lo, hi) in &self.ranges {
+            if lo == hi {
+                write!(f, "{}", lo)?;
+            } else {
+                write!(f, "{}-{}", lo, hi)?;
+            }
+        }
+        write!(f, "]{}", self.mode.spec_suffix())
+    }
+}
+
+/// Write combined policies, parenthesising any that bind less tightly
+fn fmt_combined(
+    f: &mut fmt::Formatter<'_>,
+    policies: &[Box<dyn Policy>],
+    sep: char,
+) -> fmt::Result {
+    for (i, policy) in policies.iter().enumerate() {
+        if i > 0 {
+            write!(f, "{}", sep)?;
+        }
+        let spec = policy.to_string();
+        if sep == '&' && split_top_level(&spec, '|').len() > 1 {
+            write!(f, "({})", spec)?;
+        } else {
+            write!(f, "{}", spec)?;
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for All {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_combined(f, &self.0, '&')
+    }
+}
+
+impl fmt::Display for Any {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_combined(f, &self.0, '|')
+    }
+}
+
+/// The names of the built-in policies accepted by [`parse_policy`]
+pub const POLICY_NAMES: [&str; 5] = [
+    "count",
+    "exactly-one",
+    "at-least-one",
+    "forbidden",
+    "class:[...]",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PolicySpecError {
+    #[error("Unknown policy \"{0}\" (expected one of {names})", names = POLICY_NAMES.join(", "))]
+    Unknown(String),
+
+    #[error("Unknown mode \"{0}\" (expected bytes, chars or graphemes)")]
+    UnknownMode(String),
+
+    #[error("Invalid character class \"{0}\"")]
+    InvalidClass(String),
+
+    #[error("Unbalanced brackets in \"{0}\"")]
+    Unbalanced(String),
+}
+
+/// Select a policy at runtime from a spec like `count&(forbidden|class:[a-z])`.
+///
+/// Policies are combined with `&` (all must be valid) and `|` (any must be
+/// valid), where `&` binds more tightly and parentheses group. A built-in
+/// policy can be suffixed with `@bytes`, `@chars` (the default) or
+/// `@graphemes` to choose the [`Mode`] it measures the password in.
+pub fn parse_policy(spec: &str) -> Result<Box<dyn Policy>, PolicySpecError> {
+    let spec = spec.trim();
+
+    let any = split_top_level(spec, '|');
+    if any.len() > 1 {
+        return Ok(Box::new(Any(any
+            .into_iter()
+            .map(parse_policy)
+            .collect::<Result<_, _>>()?)));
+    }
+
+    let all = split_top_level(spec, '&');
+    if all.len() > 1 {
+        return Ok(Box::new(All(all
+            .into_iter()
+            .map(parse_policy)
+            .collect::<Result<_, _>>()?)));
+    }
+
+    if let Some(inner) = spec.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+        if balanced(inner) {
+            return parse_policy(inner);
+        }
+    }
+
+    // An `@` inside a character class isn't a mode suffix
+    let (spec, mode) = match spec.rsplit_once('@') {
+        Some((spec, mode)) if !mode.contains(']') => (spec, mode.parse()?),
+        _ => (spec, Mode::Chars),
+    };
+
+    Ok(match spec {
+        "count" => Box::new(CountRange { mode }),
+        "exactly-one" => Box::new(ExactlyOnePosition { mode }),
+        "at-least-one" => Box::new(AtLeastOnePosition { mode }),
+        "forbidden" => Box::new(ForbiddenLetter { mode }),
+        _ if !balanced(spec) => return Err(PolicySpecError::Unbalanced(spec.to_string())),
+        _ => match spec.strip_prefix("class:") {
+            Some(class) => Box::new(CharacterClass {
+                mode,
+                ..class.parse()?
+            }),
+            None => return Err(PolicySpecError::Unknown(spec.to_string())),
+        },
+    })
+}
+
+/// Split `s` on `sep`, ignoring any inside parentheses or a character class
+fn split_top_level(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut in_class, mut start) = (0usize, false, 0);
+    for (i, c) in s.char_indices() {
+        match c {
+            '[' if !in_class => in_class = true,
+            ']' if in_class => in_class = false,
+            _ if in_class => {}
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ if c == sep && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Whether every parenthesis and class bracket in `s` is closed
+fn balanced(s: &str) -> bool {
+    let (mut depth, mut in_class) = (0isize, false);
+    for c in s.chars() {
+        match c {
+            '[' if !in_class => in_class = true,
+            ']' if in_class => in_class = false,
+            _ if in_class => {}
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        if depth < 0 {
+            return false;
+        }
+    }
+    depth == 0 && !in_class
+}
+
+/// The results of checking a whole password database against some policies
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AuditReport {
+    /// The number of entries that parsed successfully
+    pub entries: usize,
+    pub policies: Vec<PolicyReport>,
+    /// Entries failing the most policies, worst first
+    pub worst_offenders: Vec<Offender>,
+    /// Entries valid under some policies but not others
+    pub disagreements: Vec<Disagreement>,
+    pub parse_failures: Vec<ParseFailure>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PolicyReport {
+    /// The policy's spec, as accepted by [`parse_policy`]
+    pub policy: String,
+    pub valid: usize,
+    pub invalid: usize,
+    pub malformed: usize,
+    /// Invalid or malformed entries, by the entry's letter
+    pub failures_by_letter: BTreeMap<char, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Offender {
+    pub line: usize,
+    pub entry: String,
+    /// The number of policies the entry failed
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Disagreement {
+    pub line: usize,
+    pub entry: String,
+    pub valid_under: Vec<String>,
+    pub invalid_under: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParseFailure {
+    pub line: usize,
+    pub text: String,
+    pub error: String,
+}
+
+/// Check every entry in `input` against each of `policies`, keeping up to
+/// `max_offenders` of the entries that fail the most of them.
+///
+/// Malformed entries count as failures. Lines that don't parse are reported
+/// rather than aborting the audit.
+pub fn audit(input: &str, policies: &[Box<dyn Policy>], max_offenders: usize) -> AuditReport {
+    let names = policies.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+    let mut report = AuditReport {
+        policies: names
+            .iter()
+            .map(|name| PolicyReport {
+                policy: name.clone(),
+                ..PolicyReport::default()
+            })
+            .collect(),
+        ..AuditReport::default()
+    };
+    let mut offenders = Vec::new();
+
+    for (line, text) in (1..).zip(input.trim_end_matches(['\r', '\n']).lines()) {
+        let entry = match Entry::parse(text) {
+            Ok(entry) => entry,
+            Err(e) => {
+                report.parse_failures.push(ParseFailure {
+                    line,
+                    text: text.to_string(),
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
+        report.entries += 1;
+
+        let mut valid_under = Vec::new();
+        let mut invalid_under = Vec::new();
+        for ((policy, name), policy_report) in policies.iter().zip(&names).zip(&mut report.policies)
+        {
+            match policy.check(&entry) {
+                Validity::Valid => {
+                    policy_report.valid += 1;
+                    valid_under.push(name.clone());
+                    continue;
+                }
+                Validity::Invalid => policy_report.invalid += 1,
+                Validity::Malformed(_) => policy_report.malformed += 1,
+            }
+            *policy_report
+                .failures_by_letter
+                .entry(entry.letter)
+                .or_default() += 1;
+            invalid_under.push(name.clone());
+        }
+
+        if !invalid_under.is_empty() {
+            offenders.push(Offender {
+                line,
+                entry: text.to_string(),
+                failed: invalid_under.len(),
+            });
+            if !valid_under.is_empty() {
+                report.disagreements.push(Disagreement {
+                    line,
+                    entry: text.to_string(),
+                    valid_under,
+                    invalid_under,
+                });
+            }
+        }
+    }
+
+    // Stable, so ties stay in line order
+    offenders.sort_by_key(|o| std::cmp::Reverse(o.failed));
+    offenders.truncate(max_offenders);
+    report.worst_offenders = offenders;
+    report
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Parsed<'a> = Vec<Entry<'a>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type Error = ParseError;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Self::Error> {
+        Entry::parse_iter_from(input).collect()
+    }
+
+    fn part1(entries: &Self::Parsed<'_>) -> Result<Self::Answer1, Self::Error> {
+        Ok(entries.iter().filter(|entry| entry.validate_1()).count())
+    }
+
+    fn part2(entries: &Self::Parsed<'_>) -> Result<Self::Answer2, Self::Error> {
+        Ok(entries
+            .iter()
+            .filter(|entry| entry.validate_2().is_valid())
+            .count())
+    }
+}
+
+mod parser {
+    use nom::{
+        bytes::complete::take_while1,
+        character::complete::{char, digit1, satisfy, space1},
+        combinator::map_res,
+        error::{context, VerboseError},
+        sequence::{preceded, terminated, tuple},
+        IResult,
+    };
+
+    type Res<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+    fn number(input: &str) -> Res<'_, usize> {
+        map_res(digit1, str::parse)(input)
+    }
+
+    /// `a-b x: data`
+    pub fn entry(input: &str) -> Res<'_, (usize, usize, char, &str)> {
+        tuple((
+            context("a number", number),
+            preceded(context("'-'", char('-')), context("a number", number)),
+            preceded(
+                context("a space", space1),
+                terminated(
+                    context("a letter", satisfy(|c| !c.is_whitespace())),
+                    context("':'", char(':')),
+                ),
+            ),
+            preceded(
+                context("a space", space1),
+                context("a password", take_while1(|c: char| !c.is_whitespace())),
+            ),
+        ))(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_ENTRIES: &str = "1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc";
+
+    #[test]
+    fn parse() {
+        let entry = Entry::parse("1-3 a: abcde").expect("Failed to parse");
+        assert_eq!(
+            (entry.a, entry.b, entry.letter, entry.data),
+            (1, 3, 'a', "abcde")
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let syntax = |line| match Entry::parse(line) {
+            Err(ParseError::Syntax { column, expected }) => (column, expected),
+            other => panic!("Unexpected result for {:?}: {:?}", line, other.map(|_| ())),
+        };
+
+        assert_eq!(syntax(""), (1, "a number"));
+        assert_eq!(syntax("1+3 a: abcde"), (2, "'-'"));
+        assert_eq!(syntax("1-x a: abcde"), (3, "a number"));
+        assert_eq!(syntax("1-3 a abcde"), (6, "':'"));
+        assert_eq!(syntax("1-3 a:"), (7, "a space"));
+        assert_eq!(syntax("1-3 a: "), (8, "a password"));
+
+        assert!(matches!(
+            Entry::parse("1-3 a: abcde fgh"),
+            Err(ParseError::Trailing { column: 13 })
+        ));
+    }
+
+    #[test]
+    fn parse_lines() {
+        let crlf = TEST_ENTRIES.replace('\n', "\r\n") + "\r\n\r\n";
+        for input in [
+            TEST_ENTRIES.to_string(),
+            TEST_ENTRIES.to_string() + "\n",
+            crlf,
+        ] {
+            let entries = Entry::parse_iter_from(&input)
+                .collect::<Result<Vec<_>, _>>()
+                .expect("Failed to parse");
+            assert_eq!(entries.len(), 3);
+            assert_eq!(entries[2].data, "ccccccccc");
+        }
+
+        // Blank lines are only ignored at the end
+        assert!(Entry::parse_iter_from("1-3 a: abcde\n\n1-3 b: cdefg").any(|entry| entry.is_err()));
+    }
+
+    #[test]
+    fn entry_buf() {
+        let entries = Entry::parse_iter_from(TEST_ENTRIES)
+            .map(|entry| entry.map(EntryBuf::from))
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to parse");
+
+        for (entry, line) in entries.iter().zip(TEST_ENTRIES.lines()) {
+            assert_eq!(entry.to_string(), line);
+            assert_eq!(entry.as_entry(), Entry::parse(line).unwrap());
+            assert_eq!(&line.parse::<EntryBuf>().expect("Failed to parse"), entry);
+        }
+        assert!(matches!(
+            "1-3 a abcde".parse::<EntryBuf>(),
+            Err(ParseError::Syntax { column: 6, .. })
+        ));
+
+        let json = serde_json::to_string(&entries).expect("Failed to serialize");
+        assert_eq!(
+            json,
+            r#"["1-3 a: abcde","1-3 b: cdefg","2-9 c: ccccccccc"]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Vec<EntryBuf>>(&json).expect("Failed to deserialize"),
+            entries
+        );
+        assert!(serde_json::from_str::<EntryBuf>(r#""1-3 a:""#).is_err());
+    }
+
+    #[test]
+    fn read_from() {
+        let input = format!("{}\r\n\n1-3 a abcde\n1-3 a: a\n\n", TEST_ENTRIES);
+        let results = EntryBuf::read_from(input.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(results.len(), 6);
+        assert_eq!(
+            results[2].as_ref().map(ToString::to_string).ok(),
+            Some("2-9 c: ccccccccc".into())
+        );
+        assert!(matches!(
+            results[3],
+            Err(ReadError::Parse {
+                line: 4,
+                source: ParseError::Syntax { column: 1, .. }
+            })
+        ));
+        assert!(matches!(
+            results[4],
+            Err(ReadError::Parse {
+                line: 5,
+                source: ParseError::Syntax { column: 6, .. }
+            })
+        ));
+        assert!(results[5].is_ok());
+    }
+
+    #[test]
+    fn validate_2() {
+        let validate = |line| Entry::parse(line).expect("Failed to parse").validate_2();
+
+        assert_eq!(validate("1-3 a: abcde"), Validity::Valid);
+        assert_eq!(validate("1-3 b: cdefg"), Validity::Invalid);
+        assert_eq!(validate("2-9 c: ccccccccc"), Validity::Invalid);
+        assert_eq!(
+            validate("0-3 a: abcde"),
+            Validity::Malformed(PolicyError::ZeroPosition)
+        );
+        assert_eq!(
+            validate("1-6 a: abcde"),
+            Validity::Malformed(PolicyError::OutOfRange {
+                position: 6,
+                len: 5,
+                mode: Mode::Chars
+            })
+        );
+        assert_eq!(validate("1-5 a: abcde"), Validity::Valid);
+    }
+
+    fn check_all(spec: &str) -> Vec<Validity> {
+        let policy = parse_policy(spec).expect("Failed to parse policy");
+        Entry::parse_iter_from(TEST_ENTRIES)
+            .map(|entry| policy.check(&entry.expect("Failed to parse")))
+            .collect()
+    }
+
+    #[test]
+    fn policies() {
+        use Validity::*;
+
+        assert_eq!(check_all("count"), [Valid, Invalid, Valid]);
+        assert_eq!(check_all("exactly-one"), [Valid, Invalid, Invalid]);
+        assert_eq!(check_all("at-least-one"), [Valid, Invalid, Valid]);
+        assert_eq!(check_all("forbidden"), [Invalid, Valid, Invalid]);
+        assert_eq!(check_all("class:[a-e]"), [Valid, Invalid, Valid]);
+        assert_eq!(check_all("class:[^c]"), [Invalid, Invalid, Invalid]);
+
+        assert_eq!(check_all("count&exactly-one"), [Valid, Invalid, Invalid]);
+        assert_eq!(check_all("exactly-one|forbidden"), [Valid, Valid, Invalid]);
+        assert_eq!(
+            check_all("(exactly-one|forbidden)&class:[a-e]"),
+            [Valid, Invalid, Invalid]
+        );
+
+        let entry = Entry::parse("3-1 a: abcde").expect("Failed to parse");
+        let malformed = Malformed(PolicyError::EmptyRange { min: 3, max: 1 });
+        assert_eq!(CountRange::default().check(&entry), malformed);
+        assert_eq!(entry.validate_1_in(Mode::Chars), malformed);
+        assert!(!entry.validate_1());
+        assert_eq!(
+            parse_policy("count&forbidden").unwrap().check(&entry),
+            malformed
+        );
+        assert_eq!(
+            parse_policy("count|at-least-one").unwrap().check(&entry),
+            Valid
+        );
+        assert_eq!(
+            parse_policy("count|forbidden").unwrap().check(&entry),
+            malformed
+        );
+    }
+
+    #[test]
+    fn policy_specs() {
+        for spec in [
+            "count",
+            "class:[^a-z0-9_]",
+            "count&exactly-one",
+            "(count|forbidden)&class:[a-z]",
+            "count|exactly-one&forbidden",
+            "exactly-one@bytes&class:[a-z@]@graphemes",
+        ] {
+            assert_eq!(parse_policy(spec).expect(spec).to_string(), spec);
+        }
+        assert_eq!(
+            parse_policy(" ( count ) & (forbidden) ")
+                .unwrap()
+                .to_string(),
+            "count&forbidden"
+        );
+
+        assert_eq!(
+            parse_policy("count&nope").err(),
+            Some(PolicySpecError::Unknown("nope".into()))
+        );
+        assert_eq!(
+            parse_policy("class:[z-a]").err(),
+            Some(PolicySpecError::InvalidClass("[z-a]".into()))
+        );
+        assert_eq!(parse_policy("count@chars").unwrap().to_string(), "count");
+        assert_eq!(
+            parse_policy("count@words").err(),
+            Some(PolicySpecError::UnknownMode("words".into()))
+        );
+        assert_eq!(
+            parse_policy("(count").err(),
+            Some(PolicySpecError::Unbalanced("(count".into()))
+        );
+    }
+
+    #[test]
+    fn modes() {
+        let entry = Entry::parse("1-3 a: abcde").expect("Failed to parse");
+        for mode in [Mode::Bytes, Mode::Chars, Mode::Graphemes] {
+            assert_eq!(entry.validate_1_in(mode), Validity::Valid);
+            assert_eq!(entry.validate_2_in(mode), Validity::Valid);
+        }
+
+        // "é" is two bytes, and here a combining accent makes "e\u{301}" a
+        // single grapheme of two chars
+        let entry = Entry::parse("2-4 e: \u{e9}e\u{301}ee").expect("Failed to parse");
+        let non_ascii = Validity::Malformed(PolicyError::NonAscii { found: '\u{e9}' });
+        assert_eq!(entry.validate_1_in(Mode::Bytes), non_ascii);
+        assert_eq!(entry.validate_2_in(Mode::Bytes), non_ascii);
+
+        assert_eq!(entry.count_in(Mode::Chars), Ok(3));
+        assert_eq!(entry.count_in(Mode::Graphemes), Ok(2));
+        assert_eq!(entry.validate_2_in(Mode::Chars), Validity::Invalid);
+        assert_eq!(entry.validate_2_in(Mode::Graphemes), Validity::Valid);
+
+        let entry = Entry::parse("1-5 e: e\u{301}ee").expect("Failed to parse");
+        assert_eq!(
+            entry.validate_2_in(Mode::Graphemes),
+            Validity::Malformed(PolicyError::OutOfRange {
+                position: 5,
+                len: 3,
+                mode: Mode::Graphemes
+            })
+        );
+        assert_eq!(
+            PolicyError::OutOfRange {
+                position: 5,
+                len: 3,
+                mode: Mode::Graphemes
+            }
+            .to_string(),
+            "Position 5 is past the end of the 3 grapheme password"
+        );
+        assert_eq!(Mode::Bytes.len("e\u{301}"), 3);
+    }
+
+    #[test]
+    fn audit_modes() {
+        let policies = [
+            "exactly-one@bytes",
+            "exactly-one",
+            "exactly-one@graphemes",
+            "forbidden@graphemes",
+            "class:[a-z]@bytes",
+        ]
+        .map(|spec| parse_policy(spec).unwrap());
+        let input = "1-3 a: abcde\n2-4 e: \u{e9}e\u{301}ee\n";
+        let report = audit(input, &policies, 2);
+
+        let counts = report
+            .policies
+            .iter()
+            .map(|p| (p.policy.as_str(), p.valid, p.invalid, p.malformed))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            [
+                ("exactly-one@bytes", 1, 0, 1),
+                ("exactly-one", 1, 1, 0),
+                ("exactly-one@graphemes", 2, 0, 0),
+                ("forbidden@graphemes", 0, 2, 0),
+                ("class:[a-z]@bytes", 1, 0, 1),
+            ]
+        );
+
+        let entry = Entry::parse("1-1 e: e\u{301}").expect("Failed to parse");
+        let forbidden = ForbiddenLetter {
+            mode: Mode::Graphemes,
+        };
+        assert_eq!(forbidden.check(&entry), Validity::Valid);
+        assert_eq!(ForbiddenLetter::default().check(&entry), Validity::Invalid);
+    }
+
+    #[test]
+    fn audit_report() {
+        let policies =
+            ["count", "exactly-one", "forbidden"].map(|spec| parse_policy(spec).unwrap());
+        let input = format!("{}\n1-3 d abc\n3-1 d: dddd\n", TEST_ENTRIES);
+        let report = audit(&input, &policies, 2);
+
+        assert_eq!(report.entries, 4);
+        let counts = report
+            .policies
+            .iter()
+            .map(|p| (p.policy.as_str(), p.valid, p.invalid, p.malformed))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            [
+                ("count", 2, 1, 1),
+                ("exactly-one", 1, 3, 0),
+                ("forbidden", 1, 3, 0),
+            ]
+        );
+        assert_eq!(
+            report.policies[1].failures_by_letter,
+            BTreeMap::from([('b', 1), ('c', 1), ('d', 1)])
+        );
+
+        let offenders = report
+            .worst_offenders
+            .iter()
+            .map(|o| (o.line, o.failed))
+            .collect::<Vec<_>>();
+        assert_eq!(offenders, [(5, 3), (2, 2)]);
+
+        let disagreement = &report.disagreements[1];
+        assert_eq!(disagreement.line, 2);
+        assert_eq!(disagreement.valid_under, ["forbidden"]);
+        assert_eq!(disagreement.invalid_under, ["count", "exactly-one"]);
+        assert_eq!(report.disagreements.len(), 3);
+
+        assert_eq!(
+            report.parse_failures,
+            [ParseFailure {
+                line: 4,
+                text: "1-3 d abc".into(),
+                error: "Expected ':' at column 6".into()
+            }]
+        );
+
+        let json = serde_json::to_value(&report).expect("Failed to serialize");
+        assert_eq!(json["policies"][0]["failures_by_letter"]["d"], 1);
+        assert_eq!(json["parse_failures"][0]["line"], 4);
+    }
 
-        a ^ b
+    #[test]
+    fn test_solution() {
+        let entries = Day02::parse(TEST_ENTRIES).expect("Failed to parse");
+        assert_eq!(Day02::part1(&entries).expect("Part 1 failed"), 2);
+        assert_eq!(Day02::part2(&entries).expect("Part 2 failed"), 1);
     }
 }
@@ -1,3 +1,14 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    num::NonZeroUsize,
+    ops::RangeInclusive,
+};
+
+use itertools::Either;
+
+use crate::Solution;
+
 // pub struct Field<const R: usize, const C: usize>([[bool; C]; R]);
 
 // Don't need to fully parse to traverse the tree
@@ -10,12 +21,174 @@ impl<'a> Field<'a> {
     }
 
     /// Traverse the field using a iterator cycle to increment columns
+    pub fn traverse_cycle(&self, rise: usize, run: usize) -> usize {
+        self.visit_cycle(rise, run)
+            .filter(|&(_, _, tree)| tree)
+            .count()
+    }
+
+    /// Traverse the field using a vector with modulo index to increment columns
+    pub fn traverse_collect(&self, rise: usize, run: usize) -> usize {
+        self.visit_collect(rise, run)
+            .filter(|&(_, _, tree)| tree)
+            .count()
+    }
+
+    /// The `(row, column, is_tree)` of each cell [`Self::traverse_cycle`]
+    /// checks, where the column isn't wrapped to the pattern's width
+    pub fn visit_cycle(
+        &self,
+        rise: usize,
+        run: usize,
+    ) -> impl Iterator<Item = (usize, usize, bool)> + '_ {
+        let mut col = 0;
+        (0..)
+            .step_by(rise)
+            .zip(self.0.lines().step_by(rise))
+            .map(move |(row, line)| {
+                let visit = (row, col, line.chars().cycle().nth(col) == Some('#'));
+                col += run;
+                visit
+            })
+    }
+
+    /// The `(row, column, is_tree)` of each cell [`Self::traverse_collect`]
+    /// checks, where the column isn't wrapped to the pattern's width
+    pub fn visit_collect(
+        &self,
+        rise: usize,
+        run: usize,
+    ) -> impl Iterator<Item = (usize, usize, bool)> + '_ {
+        let mut col = 0;
+        (0..)
+            .step_by(rise)
+            .zip(self.0.lines().step_by(rise))
+            .map(move |(row, line)| {
+                let chars = line.chars().collect::<Vec<_>>();
+                let visit = (row, col, chars[col % chars.len()] == '#');
+                col += run;
+                visit
+            })
+    }
+}
+
+/// A square of the map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cell {
+    Open,
+    Tree,
+}
+
+impl Cell {
+    pub fn is_tree(self) -> bool {
+        self == Cell::Tree
+    }
+}
+
+impl TryFrom<char> for Cell {
+    type Error = char;
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(Cell::Open),
+            '#' => Ok(Cell::Tree),
+            other => Err(other),
+        }
+    }
+}
+
+impl From<Cell> for char {
+    fn from(cell: Cell) -> Self {
+        match cell {
+            Cell::Open => '.',
+            Cell::Tree => '#',
+        }
+    }
+}
+
+/// A fully parsed, rectangular map, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid {
+    width: usize,
+    cells: Vec<Cell>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum GridError {
+    #[error("The map is empty")]
+    Empty,
+
+    #[error("Row {line} has {found} cells, but the first row has {expected}")]
+    Ragged {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Unknown cell '{found}' at line {line}, column {column}")]
+    UnknownCell {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+}
+
+impl Grid {
+    /// Parse one row per line, ignoring trailing blank lines
+    pub fn parse(s: &str) -> Result<Self, GridError> {
+        let mut cells = Vec::new();
+        let (width, _) = parse_rows(s, |row| cells.extend_from_slice(row))?;
+        Ok(Self { width, cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len() / self.width
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<Cell> {
+        self.row(row)?.get(col).copied()
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[Cell]> {
+        self.cells.chunks_exact(self.width).nth(row)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> + '_ {
+        self.cells.chunks_exact(self.width)
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = Cell> + '_ {
+        self.cells
+            .iter()
+            .skip(col)
+            .step_by(self.width)
+            .take(if col < self.width { self.height() } else { 0 })
+            .copied()
+    }
+
+    /// Count the trees along any slope
+    pub fn traverse_path(&self, slope: Slope, wrap: Wrap) -> usize {
+        slope
+            .path(self.width, self.height(), wrap)
+            .filter(|&(row, col)| self.cells[row * self.width + col].is_tree())
+            .count()
+    }
+
+    /// Traverse the grid using a iterator cycle to increment columns
     pub fn traverse_cycle(&self, rise: usize, run: usize) -> usize {
         let mut trees = 0;
         let mut col = 0;
 
-        for row in self.0.lines().step_by(rise) {
-            if row.chars().cycle().nth(col) == Some('#') {
+        for row in self.rows().step_by(rise) {
+            if row
+                .iter()
+                .cycle()
+                .nth(col)
+                .is_some_and(|cell| cell.is_tree())
+            {
                 trees += 1;
             }
             col += run
@@ -24,14 +197,13 @@ impl<'a> Field<'a> {
         trees
     }
 
-    /// Traverse the field using a vector with modulo index to increment columns
+    /// Traverse the grid using a modulo index to increment columns
     pub fn traverse_collect(&self, rise: usize, run: usize) -> usize {
         let mut trees = 0;
         let mut col = 0;
 
-        for row in self.0.lines().step_by(rise) {
-            let chars = row.chars().collect::<Vec<_>>();
-            if chars[(col % chars.len())] == '#' {
+        for row in self.rows().step_by(rise) {
+            if row[col % self.width].is_tree() {
                 trees += 1;
             }
             col += run
@@ -40,3 +212,950 @@ impl<'a> Field<'a> {
         trees
     }
 }
+
+/// Parse and validate each row of a map in turn, returning its dimensions
+fn parse_rows(s: &str, mut push_row: impl FnMut(&[Cell])) -> Result<(usize, usize), GridError> {
+    let mut width = None;
+    let mut height = 0;
+    let mut row = Vec::new();
+
+    for (line, text) in (1..).zip(s.trim_end_matches(['\r', '\n']).lines()) {
+        row.clear();
+        for (column, c) in (1..).zip(text.chars()) {
+            let cell = Cell::try_from(c).map_err(|found| GridError::UnknownCell {
+                line,
+                column,
+                found,
+            })?;
+            row.push(cell);
+        }
+
+        let expected = *width.get_or_insert(row.len());
+        if row.len() != expected {
+            return Err(GridError::Ragged {
+                line,
+                expected,
+                found: row.len(),
+            });
+        }
+        push_row(&row);
+        height += 1;
+    }
+
+    match width {
+        Some(width) if width > 0 => Ok((width, height)),
+        _ => Err(GridError::Empty),
+    }
+}
+
+impl TryFrom<&Field<'_>> for Grid {
+    type Error = GridError;
+    fn try_from(field: &Field<'_>) -> Result<Self, Self::Error> {
+        Self::parse(field.0)
+    }
+}
+
+/// A map packed one bit per cell (set for trees), stored both row by row and
+/// column by column so whole rows and columns can be counted with popcount
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    /// `width` bits per row, padded to a whole number of words
+    rows: Vec<u64>,
+    /// `height` bits per column, padded to a whole number of words
+    cols: Vec<u64>,
+}
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+fn words_for(bits: usize) -> usize {
+    bits.div_ceil(WORD_BITS)
+}
+
+/// Whether bit `i` is set in a word-aligned line of bits
+fn test_bit(line: &[u64], i: usize) -> bool {
+    line[i / WORD_BITS] & (1 << (i % WORD_BITS)) != 0
+}
+
+impl BitGrid {
+    /// Parse one row per line, ignoring trailing blank lines
+    pub fn parse(s: &str) -> Result<Self, GridError> {
+        let mut rows = Vec::new();
+        let (width, height) = parse_rows(s, |row| push_row_bits(&mut rows, row))?;
+        Ok(Self::from_rows(width, height, rows))
+    }
+
+    /// Build the column-major bits from the row-major ones
+    fn from_rows(width: usize, height: usize, rows: Vec<u64>) -> Self {
+        let (row_words, col_words) = (words_for(width), words_for(height));
+        let mut cols = vec![0; width * col_words];
+
+        for (row, line) in rows.chunks_exact(row_words).enumerate() {
+            for (i, &word) in line.iter().enumerate() {
+                let mut word = word;
+                while word != 0 {
+                    let col = i * WORD_BITS + word.trailing_zeros() as usize;
+                    cols[col * col_words + row / WORD_BITS] |= 1 << (row % WORD_BITS);
+                    word &= word - 1;
+                }
+            }
+        }
+
+        Self {
+            width,
+            height,
+            rows,
+            cols,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn row_bits(&self, row: usize) -> &[u64] {
+        let words = words_for(self.width);
+        &self.rows[row * words..(row + 1) * words]
+    }
+
+    fn col_bits(&self, col: usize) -> &[u64] {
+        let words = words_for(self.height);
+        &self.cols[col * words..(col + 1) * words]
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<Cell> {
+        if row >= self.height || col >= self.width {
+            return None;
+        }
+        Some(if test_bit(self.row_bits(row), col) {
+            Cell::Tree
+        } else {
+            Cell::Open
+        })
+    }
+
+    /// The number of trees in a row, if it's in the map
+    pub fn row_trees(&self, row: usize) -> Option<usize> {
+        (row < self.height).then(|| popcount(self.row_bits(row)))
+    }
+
+    /// The number of trees in a column, if it's in the map
+    pub fn col_trees(&self, col: usize) -> Option<usize> {
+        (col < self.width).then(|| popcount(self.col_bits(col)))
+    }
+
+    /// The number of trees in the whole map
+    pub fn trees(&self) -> usize {
+        popcount(&self.rows)
+    }
+
+    /// Count the trees along any slope
+    pub fn traverse_path(&self, slope: Slope, wrap: Wrap) -> usize {
+        slope
+            .path(self.width, self.height, wrap)
+            .filter(|&(row, col)| test_bit(self.row_bits(row), col))
+            .count()
+    }
+
+    /// Traverse the grid testing bits, wrapping columns with a modulo
+    pub fn traverse(&self, rise: usize, run: usize) -> usize {
+        let run = run % self.width;
+        let mut trees = 0;
+        let mut col = 0;
+
+        for row in (0..self.height).step_by(rise) {
+            if test_bit(self.row_bits(row), col) {
+                trees += 1;
+            }
+            col = (col + run) % self.width;
+        }
+
+        trees
+    }
+}
+
+/// Append a row's trees as a word-aligned line of bits
+fn push_row_bits(rows: &mut Vec<u64>, row: &[Cell]) {
+    let start = rows.len();
+    rows.resize(start + words_for(row.len()), 0);
+    for (col, cell) in row.iter().enumerate() {
+        if cell.is_tree() {
+            rows[start + col / WORD_BITS] |= 1 << (col % WORD_BITS);
+        }
+    }
+}
+
+fn popcount(words: &[u64]) -> usize {
+    words.iter().map(|word| word.count_ones() as usize).sum()
+}
+
+impl From<&Grid> for BitGrid {
+    fn from(grid: &Grid) -> Self {
+        let mut rows = Vec::with_capacity(grid.height() * words_for(grid.width));
+        for row in grid.rows() {
+            push_row_bits(&mut rows, row);
+        }
+        Self::from_rows(grid.width, grid.height(), rows)
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            let line = row.iter().map(|&cell| char::from(cell)).collect::<String>();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// A step of `down` rows and `right` columns (leftward if negative), taken
+/// from the top-left corner of the map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Slope {
+    pub down: usize,
+    pub right: isize,
+}
+
+/// What happens when a path reaches the edge of the map
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Wrap {
+    /// The pattern repeats to the left and right, and the path ends below
+    /// the bottom row, as in the puzzle
+    #[default]
+    Horizontal,
+    /// The pattern repeats in every direction, and the path ends just before
+    /// it would revisit a cell
+    Toroidal,
+    /// The path ends at any edge
+    Stop,
+}
+
+impl Slope {
+    pub const fn new(down: usize, right: isize) -> Self {
+        Self { down, right }
+    }
+
+    /// A path moving `right / per_rows` columns each row, which only counts
+    /// the cells it passes through exactly. E.g. `ratio(3, 2)` steps 2 down
+    /// and 3 right, and `ratio(2, 4)` steps 2 down and 1 right.
+    ///
+    /// Returns `None` if `per_rows` is zero.
+    pub fn ratio(right: isize, per_rows: usize) -> Option<Self> {
+        if per_rows == 0 {
+            return None;
+        }
+        let divisor = gcd(right.unsigned_abs(), per_rows);
+        Some(Self {
+            down: per_rows / divisor,
+            right: right / divisor as isize,
+        })
+    }
+
+    /// The `(row, col)` of each cell the path visits on a `width` by `height`
+    /// map, in order
+    pub fn path(
+        self,
+        width: usize,
+        height: usize,
+        wrap: Wrap,
+    ) -> impl Iterator<Item = (usize, usize)> + Clone {
+        let (wrap_rows, wrap_cols) = match wrap {
+            Wrap::Horizontal => (false, true),
+            Wrap::Toroidal => (true, true),
+            Wrap::Stop => (false, false),
+        };
+        let steps = if width == 0 || height == 0 {
+            0
+        } else {
+            path_len(height, self.down as isize, wrap_rows)
+                .min(path_len(width, self.right, wrap_cols))
+                .min(lcm(
+                    period(height, self.down as isize, wrap_rows),
+                    period(width, self.right, wrap_cols),
+                ))
+        };
+
+        // Within `steps`, unwrapped axes never leave the map, so every axis
+        // can be advanced modulo its size
+        let down = self.down % height.max(1);
+        let right = self.right.rem_euclid(width.max(1) as isize) as usize;
+        (0..steps).scan((0, 0), move |position, _| {
+            let (row, col) = *position;
+            *position = ((row + down) % height, (col + right) % width);
+            Some((row, col))
+        })
+    }
+}
+
+impl From<(usize, usize)> for Slope {
+    /// From a `(rise, run)` pair, as in [`SLOPES`]
+    fn from((rise, run): (usize, usize)) -> Self {
+        Self::new(rise, run as isize)
+    }
+}
+
+/// The steps before a path leaves one axis of the map, or `usize::MAX` if it
+/// never does
+fn path_len(size: usize, step: isize, wraps: bool) -> usize {
+    match step {
+        _ if wraps => usize::MAX,
+        0 => usize::MAX,
+        // Paths start at 0, so stepping backwards leaves immediately
+        step if step < 0 => 1,
+        step => (size - 1) / step as usize + 1,
+    }
+}
+
+/// The steps before a path returns to its starting point along one axis of the
+/// map, or `usize::MAX` if it never does
+fn period(size: usize, step: isize, wraps: bool) -> usize {
+    let step = step.rem_euclid(size as isize) as usize;
+    match step {
+        0 => 1,
+        _ if wraps => size / gcd(size, step),
+        _ => usize::MAX,
+    }
+}
+
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple, saturating at `usize::MAX`
+fn lcm(a: usize, b: usize) -> usize {
+    (a / gcd(a, b)).saturating_mul(b)
+}
+
+/// Whether a slope search is after the fewest or the most trees
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Objective {
+    Fewest,
+    Most,
+}
+
+impl Objective {
+    fn is_better(self, trees: usize, best: usize) -> bool {
+        match self {
+            Objective::Fewest => trees < best,
+            Objective::Most => trees > best,
+        }
+    }
+}
+
+/// The slopes tied for the best tree count in a search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BestSlopes {
+    pub trees: usize,
+    /// In order of rise, then run
+    pub slopes: Vec<Slope>,
+}
+
+impl Grid {
+    /// Find the slopes (with [`Wrap::Horizontal`]) in the given bounds that
+    /// hit the fewest or most trees, or `None` if either bound is empty.
+    ///
+    /// Runs that differ by a multiple of the map's width take the same path,
+    /// so each path is only traversed once per rise.
+    pub fn best_slopes(
+        &self,
+        rise: RangeInclusive<usize>,
+        run: RangeInclusive<isize>,
+        objective: Objective,
+    ) -> Option<BestSlopes> {
+        let mut best: Option<BestSlopes> = None;
+        let mut counts = vec![None; self.width];
+
+        for down in rise {
+            counts.fill(None);
+            for right in run.clone() {
+                let residue = right.rem_euclid(self.width as isize) as usize;
+                let trees = *counts[residue].get_or_insert_with(|| {
+                    self.traverse_path(Slope::new(down, residue as isize), Wrap::Horizontal)
+                });
+
+                let slope = Slope::new(down, right);
+                match &mut best {
+                    Some(best) if best.trees == trees => best.slopes.push(slope),
+                    Some(best) if !objective.is_better(trees, best.trees) => {}
+                    _ => {
+                        best = Some(BestSlopes {
+                            trees,
+                            slopes: vec![slope],
+                        })
+                    }
+                }
+            }
+        }
+
+        best
+    }
+}
+
+/// A cell of a [`Rendering`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mark {
+    Open,
+    Tree,
+    /// Open ground the path crosses
+    PathOpen,
+    /// A tree the path hits
+    PathTree,
+}
+
+impl Mark {
+    fn char(self) -> char {
+        match self {
+            Mark::Open => '.',
+            Mark::Tree => '#',
+            Mark::PathOpen => 'O',
+            Mark::PathTree => 'X',
+        }
+    }
+
+    fn rgb(self) -> [u8; 3] {
+        match self {
+            Mark::Open => [255, 255, 255],
+            Mark::Tree => [34, 139, 34],
+            Mark::PathOpen => [30, 144, 255],
+            Mark::PathTree => [220, 20, 60],
+        }
+    }
+}
+
+/// A path drawn on a map, with the pattern repeated horizontally as many
+/// times as the path needs (to the left, for leftward slopes).
+///
+/// Displays as in the puzzle, with `O` for open ground the path crosses and
+/// `X` for the trees it hits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rendering {
+    width: usize,
+    marks: Vec<Mark>,
+}
+
+/// Which of [`Field`]'s traversals a [`Rendering`] draws
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Traversal {
+    Cycle,
+    Collect,
+}
+
+impl Rendering {
+    /// Draw the path of `slope` (with [`Wrap::Horizontal`]) on `grid`
+    pub fn new(grid: &Grid, slope: Slope) -> Self {
+        let steps = slope
+            .path(grid.width, grid.height(), Wrap::Horizontal)
+            .count();
+        let width = grid.width as isize;
+        let visits = (0..steps).map(|k| {
+            let (row, col) = (k * slope.down, k as isize * slope.right);
+            let cell = grid.cells[row * grid.width + col.rem_euclid(width) as usize];
+            (row, col, cell.is_tree())
+        });
+        Self::draw(grid, visits)
+    }
+
+    /// Draw the cells `traversal` of `field` checks, marking each as a hit
+    /// tree exactly when the traversal counted it as one.
+    ///
+    /// Rendering both traversals of the same slope shows any cell where they
+    /// disagree.
+    pub fn from_field(
+        field: &Field<'_>,
+        rise: usize,
+        run: usize,
+        traversal: Traversal,
+    ) -> Result<Self, GridError> {
+        let grid = Grid::parse(field.0)?;
+        let visits = match traversal {
+            Traversal::Cycle => Either::Left(field.visit_cycle(rise, run)),
+            Traversal::Collect => Either::Right(field.visit_collect(rise, run)),
+        };
+        Ok(Self::draw(
+            &grid,
+            visits.map(|(row, col, tree)| (row, col as isize, tree)),
+        ))
+    }
+
+    /// Draw `(row, column, is_tree)` visits on `grid`, with columns unwrapped
+    fn draw(grid: &Grid, visits: impl Iterator<Item = (usize, isize, bool)>) -> Self {
+        let visits = visits.collect::<Vec<_>>();
+        let cols = visits.iter().map(|&(_, col, _)| col);
+        let (min, max) = (cols.clone().min().unwrap_or(0), cols.max().unwrap_or(0));
+
+        // Whole copies of the pattern needed either side of the original
+        let width = grid.width as isize;
+        let left = (-min + width - 1) / width;
+        let right = max / width + 1;
+
+        let tiles = (left + right) as usize;
+        let mut rendering = Self {
+            width: tiles * grid.width,
+            marks: Vec::with_capacity(tiles * grid.cells.len()),
+        };
+        for row in grid.rows() {
+            for _ in 0..tiles {
+                rendering.marks.extend(row.iter().map(|&cell| match cell {
+                    Cell::Open => Mark::Open,
+                    Cell::Tree => Mark::Tree,
+                }));
+            }
+        }
+
+        for (row, col, tree) in visits {
+            let col = (col + left * width) as usize;
+            rendering.marks[row * rendering.width + col] =
+                if tree { Mark::PathTree } else { Mark::PathOpen };
+        }
+
+        rendering
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.marks.len() / self.width
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Mark]> + '_ {
+        self.marks.chunks_exact(self.width)
+    }
+
+    /// The number of trees the path hits
+    pub fn trees(&self) -> usize {
+        self.marks.iter().filter(|&&m| m == Mark::PathTree).count()
+    }
+
+    /// Write as a plain (ASCII) PPM image, with each cell as a `scale` pixel
+    /// square.
+    ///
+    /// Each row of pixels starts a new line, wrapped to the 70 characters the
+    /// format allows.
+    pub fn write_ppm(&self, mut out: impl Write, scale: NonZeroUsize) -> io::Result<()> {
+        const MAX_LINE: usize = 70;
+
+        let scale = scale.get();
+        writeln!(
+            out,
+            "P3\n{} {}\n255",
+            self.width * scale,
+            self.height() * scale
+        )?;
+        for row in self.rows() {
+            let mut lines = String::new();
+            let mut line_len = 0;
+            for [r, g, b] in row
+                .iter()
+                .flat_map(|mark| std::iter::repeat_n(mark.rgb(), scale))
+            {
+                let pixel = format!("{} {} {}", r, g, b);
+                if line_len > 0 && line_len + 1 + pixel.len() > MAX_LINE {
+                    lines.push('\n');
+                    line_len = 0;
+                } else if line_len > 0 {
+                    lines.push(' ');
+                    line_len += 1;
+                }
+                lines.push_str(&pixel);
+                line_len += pixel.len();
+            }
+            for _ in 0..scale {
+                writeln!(out, "{}", lines)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Rendering {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            let line = row.iter().map(|mark| mark.char()).collect::<String>();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// The `(rise, run)` slopes checked for part 2
+pub const SLOPES: [(usize, usize); 5] = [(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+
+    type Parsed<'a> = Grid;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type Error = GridError;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Self::Error> {
+        Grid::parse(input)
+    }
+
+    fn part1(grid: &Self::Parsed<'_>) -> Result<Self::Answer1, Self::Error> {
+        Ok(grid.traverse_collect(1, 3))
+    }
+
+    fn part2(grid: &Self::Parsed<'_>) -> Result<Self::Answer2, Self::Error> {
+        Ok(SLOPES
+            .iter()
+            .map(|&(rise, run)| grid.traverse_collect(rise, run))
+            .product())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_FIELD: &str = "..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#
+";
+
+    #[test]
+    fn grid() {
+        let grid = Grid::parse(TEST_FIELD).expect("Failed to parse");
+        assert_eq!((grid.width(), grid.height()), (11, 11));
+        assert_eq!(grid.get(0, 2), Some(Cell::Tree));
+        assert_eq!(grid.get(0, 11), None);
+        assert_eq!(grid.column(0).filter(|c| c.is_tree()).count(), 3);
+        assert_eq!(grid.column(11).count(), 0);
+        assert_eq!(grid.to_string(), TEST_FIELD);
+
+        let field = Field::new(TEST_FIELD);
+        for &(rise, run) in &SLOPES {
+            let expected = field.traverse_cycle(rise, run);
+            assert_eq!(field.traverse_collect(rise, run), expected);
+            assert_eq!(grid.traverse_cycle(rise, run), expected);
+            assert_eq!(grid.traverse_collect(rise, run), expected);
+        }
+    }
+
+    #[test]
+    fn bit_grid() {
+        let grid = Grid::parse(TEST_FIELD).expect("Failed to parse");
+        let bits = BitGrid::parse(TEST_FIELD).expect("Failed to parse");
+        assert_eq!(BitGrid::from(&grid), bits);
+        assert_eq!((bits.width(), bits.height()), (11, 11));
+
+        for row in 0..grid.height() {
+            for col in 0..grid.width() {
+                assert_eq!(bits.get(row, col), grid.get(row, col));
+            }
+            let trees = grid
+                .row(row)
+                .unwrap()
+                .iter()
+                .filter(|c| c.is_tree())
+                .count();
+            assert_eq!(bits.row_trees(row), Some(trees));
+        }
+        for col in 0..grid.width() {
+            let trees = grid.column(col).filter(|c| c.is_tree()).count();
+            assert_eq!(bits.col_trees(col), Some(trees));
+        }
+        assert_eq!(
+            (bits.get(11, 0), bits.row_trees(11), bits.col_trees(11)),
+            (None, None, None)
+        );
+        assert_eq!(bits.trees(), TEST_FIELD.matches('#').count());
+
+        for &(rise, run) in &SLOPES {
+            assert_eq!(bits.traverse(rise, run), grid.traverse_collect(rise, run));
+        }
+        assert_eq!(bits.traverse(1, 3 + 11 * 5), 7);
+    }
+
+    #[test]
+    fn bit_grid_multiword() {
+        // Wider and taller than a word, with trees on the diagonal and edges
+        let size = 130;
+        let input = (0..size)
+            .map(|row| {
+                (0..size)
+                    .map(|col| {
+                        if col == row || col == size - 1 {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect::<String>();
+        let grid = Grid::parse(&input).expect("Failed to parse");
+        let bits = BitGrid::parse(&input).expect("Failed to parse");
+
+        assert_eq!(bits.row_trees(0), Some(2));
+        assert_eq!(bits.row_trees(size - 1), Some(1));
+        assert_eq!(bits.col_trees(64), Some(1));
+        assert_eq!(bits.col_trees(size - 1), Some(size));
+        assert_eq!(bits.traverse(1, 1), size);
+        for (rise, run) in [(1, 3), (2, 65), (3, 129), (1, 200)] {
+            assert_eq!(bits.traverse(rise, run), grid.traverse_collect(rise, run));
+        }
+    }
+
+    #[test]
+    fn slopes() {
+        let grid = Grid::parse(TEST_FIELD).expect("Failed to parse");
+        let bits = BitGrid::from(&grid);
+        for &(rise, run) in &SLOPES {
+            let slope = Slope::from((rise, run));
+            assert_eq!(
+                grid.traverse_path(slope, Wrap::Horizontal),
+                grid.traverse_collect(rise, run)
+            );
+            assert_eq!(
+                bits.traverse_path(slope, Wrap::Toroidal),
+                grid.traverse_path(slope, Wrap::Toroidal)
+            );
+        }
+
+        // Leftward runs wrap to the right edge
+        let left = Slope::new(1, -3);
+        let path = left.path(11, 11, Wrap::Horizontal).collect::<Vec<_>>();
+        assert_eq!(path[..3], [(0, 0), (1, 8), (2, 5)]);
+        assert_eq!(path.len(), 11);
+        assert_eq!(left.path(11, 11, Wrap::Stop).count(), 1);
+
+        // Only whole cells are counted, so 3 right per 2 down skips odd rows
+        assert_eq!(Slope::ratio(3, 2), Some(Slope::new(2, 3)));
+        assert_eq!(Slope::ratio(-2, 4), Some(Slope::new(2, -1)));
+        assert_eq!(Slope::ratio(0, 3), Some(Slope::new(1, 0)));
+        assert_eq!(Slope::ratio(1, 0), None);
+        assert_eq!(
+            grid.traverse_path(Slope::ratio(1, 2).unwrap(), Wrap::Horizontal),
+            grid.traverse_collect(2, 1)
+        );
+    }
+
+    #[test]
+    fn wrap_modes() {
+        let slope = Slope::new(1, 3);
+        assert_eq!(slope.path(11, 11, Wrap::Horizontal).count(), 11);
+        // Both axes repeat every 11 steps, so the path loops back to the start
+        assert_eq!(slope.path(11, 11, Wrap::Toroidal).count(), 11);
+        assert_eq!(slope.path(11, 11, Wrap::Stop).count(), 4);
+        assert_eq!(slope.path(4, 6, Wrap::Toroidal).count(), 12);
+
+        let toroidal = slope.path(4, 6, Wrap::Toroidal).collect::<Vec<_>>();
+        let distinct = toroidal.iter().collect::<std::collections::HashSet<_>>();
+        assert_eq!(distinct.len(), toroidal.len());
+
+        // Purely horizontal paths end once they loop round the row
+        assert_eq!(Slope::new(0, 4).path(6, 5, Wrap::Horizontal).count(), 3);
+        assert_eq!(Slope::new(0, 4).path(6, 5, Wrap::Stop).count(), 2);
+        assert_eq!(Slope::new(0, 0).path(6, 5, Wrap::Toroidal).count(), 1);
+        assert_eq!(Slope::new(5, 0).path(6, 5, Wrap::Toroidal).count(), 1);
+        assert_eq!(Slope::new(2, 0).path(6, 5, Wrap::Toroidal).count(), 5);
+        assert_eq!(Slope::new(1, 1).path(0, 5, Wrap::Toroidal).count(), 0);
+    }
+
+    #[test]
+    fn best_slopes() {
+        let grid = Grid::parse(TEST_FIELD).expect("Failed to parse");
+        let (rise, run) = (1..=4, -15..=15);
+
+        for objective in [Objective::Fewest, Objective::Most] {
+            let best = grid
+                .best_slopes(rise.clone(), run.clone(), objective)
+                .expect("No slopes searched");
+
+            let all = rise
+                .clone()
+                .flat_map(|down| run.clone().map(move |right| Slope::new(down, right)))
+                .map(|slope| (slope, grid.traverse_path(slope, Wrap::Horizontal)))
+                .collect::<Vec<_>>();
+            let trees = all.iter().map(|&(_, trees)| trees);
+            let expected = match objective {
+                Objective::Fewest => trees.min(),
+                Objective::Most => trees.max(),
+            }
+            .unwrap();
+            let slopes = all
+                .iter()
+                .filter(|&&(_, trees)| trees == expected)
+                .map(|&(slope, _)| slope)
+                .collect::<Vec<_>>();
+
+            assert_eq!(best.trees, expected);
+            assert_eq!(best.slopes, slopes);
+        }
+
+        let best = grid
+            .best_slopes(1..=1, 0..=7, Objective::Most)
+            .expect("No slopes searched");
+        assert_eq!((best.trees, best.slopes), (7, vec![Slope::new(1, 3)]));
+
+        // Runs 11 apart are the same path, so tie
+        let best = grid
+            .best_slopes(1..=1, 3..=14, Objective::Most)
+            .expect("No slopes searched");
+        assert_eq!(best.slopes, [Slope::new(1, 3), Slope::new(1, 14)]);
+
+        let empty = RangeInclusive::new(3, 2);
+        assert_eq!(grid.best_slopes(1..=1, empty, Objective::Fewest), None);
+    }
+
+    #[test]
+    fn rendering() {
+        let grid = Grid::parse(TEST_FIELD).expect("Failed to parse");
+        let rendering = Rendering::new(&grid, Slope::new(1, 3));
+        assert_eq!((rendering.width(), rendering.height()), (33, 11));
+        assert_eq!(rendering.trees(), 7);
+
+        // Folding the copies back onto the original gives the puzzle's map
+        let folded = rendering
+            .rows()
+            .map(|row| {
+                (0..11)
+                    .map(|col| {
+                        row.iter()
+                            .skip(col)
+                            .step_by(11)
+                            .find(|m| matches!(m, Mark::PathOpen | Mark::PathTree))
+                            .unwrap_or(&row[col])
+                            .char()
+                    })
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect::<String>();
+        assert_eq!(
+            folded,
+            "O.##.......
+#..O#...#..
+.#....X..#.
+..#.#...#O#
+.X...##..#.
+..#.X#.....
+.#.#.#.O..#
+.#........X
+#.X#...#...
+#...#X....#
+.#..#...X.#
+"
+        );
+
+        let field = Field::new(TEST_FIELD);
+        for &(rise, run) in &SLOPES {
+            let rendering = Rendering::new(&grid, Slope::from((rise, run)));
+            assert_eq!(rendering.trees(), field.traverse_cycle(rise, run));
+            assert_eq!(rendering.trees(), field.traverse_collect(rise, run));
+
+            // Each traversal of the field draws the same path
+            for traversal in [Traversal::Cycle, Traversal::Collect] {
+                let drawn =
+                    Rendering::from_field(&field, rise, run, traversal).expect("Failed to parse");
+                assert_eq!(drawn.trees(), rendering.trees());
+                assert_eq!(drawn.to_string(), rendering.to_string());
+            }
+        }
+        assert_eq!(
+            Rendering::from_field(&Field::new("..#\n.#\n"), 1, 1, Traversal::Cycle).err(),
+            Some(GridError::Ragged {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+
+        // Leftward paths extend the pattern to the left
+        let rendering = Rendering::new(&grid, Slope::new(2, -4));
+        assert_eq!(rendering.width(), 33);
+        let text = rendering.to_string();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(&lines[0][22..], "O.##.......");
+        assert_eq!(lines[2].find(['O', 'X']), Some(18));
+        assert_eq!(lines[1].find(['O', 'X']), None);
+        assert_eq!(
+            rendering.trees(),
+            grid.traverse_path(Slope::new(2, -4), Wrap::Horizontal)
+        );
+    }
+
+    #[test]
+    fn rendering_ppm() {
+        let grid = Grid::parse("#.\n..\n").expect("Failed to parse");
+        let rendering = Rendering::new(&grid, Slope::new(1, 1));
+        assert_eq!(rendering.to_string(), "X.\n.O\n");
+
+        let mut ppm = Vec::new();
+        let scale = NonZeroUsize::new(2).unwrap();
+        rendering
+            .write_ppm(&mut ppm, scale)
+            .expect("Failed to write");
+        let ppm = String::from_utf8(ppm).expect("PPM isn't ASCII");
+        let lines = ppm.lines().collect::<Vec<_>>();
+        assert_eq!(lines[..3], ["P3", "4 4", "255"]);
+        assert_eq!(lines.len(), 3 + 4);
+        assert_eq!(lines[3], "220 20 60 220 20 60 255 255 255 255 255 255");
+        assert_eq!(lines[6], lines[5]);
+
+        // Wide rows wrap, without splitting any pixel across lines
+        let grid = Grid::parse(&format!("{}\n", ".".repeat(10))).expect("Failed to parse");
+        let mut ppm = Vec::new();
+        Rendering::new(&grid, Slope::new(1, 1))
+            .write_ppm(&mut ppm, scale)
+            .expect("Failed to write");
+        let ppm = String::from_utf8(ppm).expect("PPM isn't ASCII");
+        let pixels = ppm.lines().skip(3).collect::<Vec<_>>();
+        assert!(pixels.len() > 2);
+        assert!(pixels.iter().all(|line| line.len() <= 70));
+        assert!(pixels.iter().all(|line| line.split(' ').count() % 3 == 0));
+        let values = pixels.iter().flat_map(|line| line.split(' ')).count();
+        assert_eq!(values, 20 * 2 * 3);
+    }
+
+    #[test]
+    fn grid_errors() {
+        assert_eq!(Grid::parse("\n"), Err(GridError::Empty));
+        assert_eq!(
+            Grid::parse("..#\n.#\n"),
+            Err(GridError::Ragged {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            Grid::parse("..#\n.O.\n"),
+            Err(GridError::UnknownCell {
+                line: 2,
+                column: 2,
+                found: 'O'
+            })
+        );
+    }
+
+    #[test]
+    fn test_solution() {
+        let grid = Day03::parse(TEST_FIELD).expect("Failed to parse");
+        assert_eq!(Day03::part1(&grid).expect("Part 1 failed"), 7);
+        assert_eq!(Day03::part2(&grid).expect("Part 2 failed"), 336);
+    }
+}
@@ -1,9 +1,11 @@
-use std::str::FromStr;
+use std::{convert::Infallible, str::FromStr};
 
 use serde::Deserialize;
 use validator::{Validate, ValidationError, ValidationErrors};
 
-#[derive(Debug, Deserialize)]
+use crate::Solution;
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct RawPassport<'a> {
     #[serde(rename = "byr")]
     birth_year: &'a str,
@@ -30,6 +32,8 @@ pub struct RawPassport<'a> {
     country_id: Option<&'a str>,
 }
 
+// Not every field is read after parsing, but parsing them *is* the validation
+#[allow(dead_code)]
 #[derive(Debug, /*Deserialize,*/ Validate)]
 pub struct Passport<'a> {
     // #[serde(rename = "byr")]
@@ -136,6 +140,7 @@ impl FromStr for Height {
     }
 }
 
+#[allow(dead_code)]
 #[derive(Debug)]
 pub struct HexColor {
     red: u8,
@@ -207,6 +212,7 @@ impl FromStr for EyeColor {
     }
 }
 
+#[allow(dead_code)]
 #[derive(Debug)]
 pub struct Id<'a>(&'a str);
 
@@ -340,3 +346,34 @@ pub enum PassportValueError {
     EyeColor(String),
     Id,
 }
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    /// Passports with all of the required keys present
+    type Parsed<'a> = Vec<RawPassport<'a>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type Error = Infallible;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Self::Error> {
+        Ok(RawPassport::parse_iter_from(input)
+            .filter_map(Result::ok)
+            .collect())
+    }
+
+    fn part1(raw: &Self::Parsed<'_>) -> Result<Self::Answer1, Self::Error> {
+        Ok(raw.len())
+    }
+
+    fn part2(raw: &Self::Parsed<'_>) -> Result<Self::Answer2, Self::Error> {
+        Ok(raw
+            .iter()
+            .cloned()
+            .filter_map(|r| Passport::try_from(r).ok())
+            .filter(|p| p.validate().is_ok())
+            .count())
+    }
+}
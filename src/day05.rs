@@ -1,4 +1,9 @@
-use std::str::FromStr;
+use std::{collections::BTreeSet, io::BufRead, str::FromStr};
+
+use crate::{
+    reader::{self, ReadError},
+    Solution,
+};
 
 pub struct BoardingPass(u8, u8);
 
@@ -16,6 +21,14 @@ impl BoardingPass {
 
         row * 8 + col
     }
+
+    /// Parse one boarding pass per line of `reader`, holding only a line at a
+    /// time
+    pub fn read_from(
+        reader: impl BufRead,
+    ) -> impl Iterator<Item = Result<BoardingPass, ReadError<BoardingPassParseError>>> {
+        reader::parse_lines(reader, BoardingPass::from_str)
+    }
 }
 
 impl FromStr for BoardingPass {
@@ -72,14 +85,68 @@ impl FromStr for BoardingPass {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, thiserror::Error)]
 pub enum BoardingPassParseError {
+    #[error("Not enough partitions in the boarding pass")]
     NotEnough,
+    #[error("Too many partitions in the boarding pass")]
     TooMuch,
 
+    #[error("Unknown partition operation '{0}'")]
     UnknownOp(char),
 }
 
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Parsed<'a> = Vec<BoardingPass>;
+    type Answer1 = u16;
+    type Answer2 = u16;
+    type Error = SolveError;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Self::Error> {
+        input
+            .lines()
+            .map(|line| BoardingPass::from_str(line).map_err(SolveError::from))
+            .collect()
+    }
+
+    fn part1(passes: &Self::Parsed<'_>) -> Result<Self::Answer1, Self::Error> {
+        passes
+            .iter()
+            .map(BoardingPass::seat_id)
+            .max()
+            .ok_or(SolveError::NoPasses)
+    }
+
+    fn part2(passes: &Self::Parsed<'_>) -> Result<Self::Answer2, Self::Error> {
+        let ids = passes
+            .iter()
+            .map(BoardingPass::seat_id)
+            .collect::<BTreeSet<_>>();
+
+        ids.iter()
+            .zip(ids.iter().skip(1))
+            .find(|(&a, &b)| (b - a) > 1)
+            .map(|(before, _after)| before + 1)
+            .ok_or(SolveError::NoSeat)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SolveError {
+    #[error("Failed to parse boarding pass: {0}")]
+    Parse(#[from] BoardingPassParseError),
+
+    #[error("No boarding passes in the input")]
+    NoPasses,
+
+    #[error("No gap found in the seat ids")]
+    NoSeat,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,4 +173,22 @@ mod tests {
         assert_eq!(pass.col(), 4);
         assert_eq!(pass.seat_id(), 820);
     }
+
+    #[test]
+    fn read_from() {
+        let input = "FBFBBFFRLR\nBFFFBBFRRR\nFFFBBBFRRX\nBBFFBBFRLL\n";
+        let results = BoardingPass::read_from(input.as_bytes()).collect::<Vec<_>>();
+        let ids = results
+            .iter()
+            .map(|pass| pass.as_ref().map(BoardingPass::seat_id).ok())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [Some(357), Some(567), None, Some(820)]);
+        assert!(matches!(
+            results[2],
+            Err(ReadError::Parse {
+                line: 3,
+                source: BoardingPassParseError::UnknownOp('X')
+            })
+        ));
+    }
 }
@@ -1 +1,347 @@
-//! Empty module: Not necessary!
+use std::{
+    collections::HashSet,
+    convert::Infallible,
+    ops::{BitAnd, BitOr},
+    str::FromStr,
+};
+
+use crate::Solution;
+
+/// The questions a single person answered "yes" to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Declaration(HashSet<char>);
+
+/// The declarations of a group of people, one person per line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Group(Vec<Declaration>);
+
+impl Declaration {
+    pub fn answers(&self) -> &HashSet<char> {
+        &self.0
+    }
+}
+
+impl From<&str> for Declaration {
+    fn from(s: &str) -> Self {
+        Self(s.trim().chars().collect())
+    }
+}
+
+impl Group {
+    /// Parse groups separated by blank lines
+    pub fn parse_iter_from(s: &str) -> impl Iterator<Item = Group> + Clone + '_ {
+        let mut lines = s.lines();
+        std::iter::from_fn(move || {
+            let mut lines = lines
+                .by_ref()
+                .skip_while(|line| line.trim().is_empty())
+                .take_while(|line| !line.trim().is_empty())
+                .peekable();
+            lines.peek()?;
+            Some(Group(lines.map(Declaration::from).collect()))
+        })
+    }
+
+    pub fn declarations(&self) -> &[Declaration] {
+        &self.0
+    }
+
+    /// The questions anyone in the group answered "yes" to
+    pub fn anyone(&self) -> HashSet<char> {
+        self.0.iter().fold(HashSet::new(), |mut all, declaration| {
+            all.extend(declaration.answers());
+            all
+        })
+    }
+
+    /// The questions everyone in the group answered "yes" to
+    pub fn everyone(&self) -> HashSet<char> {
+        let mut declarations = self.0.iter();
+        let first = match declarations.next() {
+            Some(first) => first.answers().clone(),
+            None => return HashSet::new(),
+        };
+        declarations.fold(first, |all, declaration| {
+            all.intersection(declaration.answers()).cloned().collect()
+        })
+    }
+
+    pub fn count_anyone(&self) -> usize {
+        self.anyone().len()
+    }
+
+    pub fn count_everyone(&self) -> usize {
+        self.everyone().len()
+    }
+}
+
+impl From<&str> for Group {
+    fn from(s: &str) -> Self {
+        Self(
+            s.lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(Declaration::from)
+                .collect(),
+        )
+    }
+}
+
+/// A set of answers to questions `a`-`z`, packed one bit per question
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AnswerSet(u32);
+
+impl AnswerSet {
+    pub const EMPTY: Self = Self(0);
+    pub const ALL: Self = Self((1 << 26) - 1);
+
+    fn bit(question: char) -> Option<u32> {
+        question
+            .is_ascii_lowercase()
+            .then(|| 1 << (question as u32 - 'a' as u32))
+    }
+
+    /// Add a question to the set, returning `false` if it isn't `a`-`z`
+    pub fn insert(&mut self, question: char) -> bool {
+        match Self::bit(question) {
+            Some(bit) => {
+                self.0 |= bit;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains(&self, question: char) -> bool {
+        Self::bit(question).is_some_and(|bit| self.0 & bit != 0)
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = char> + '_ {
+        ('a'..='z').filter(move |&c| self.contains(c))
+    }
+
+    /// Parse one person's answers, reporting errors as if on line `line`
+    fn parse_line(s: &str, line: usize) -> Result<Self, InvalidQuestion> {
+        let mut set = Self::EMPTY;
+        for (column, found) in (1..).zip(s.trim_end().chars()) {
+            if !set.insert(found) {
+                return Err(InvalidQuestion {
+                    line,
+                    column,
+                    found,
+                });
+            }
+        }
+        Ok(set)
+    }
+}
+
+impl BitOr for AnswerSet {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl BitAnd for AnswerSet {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl FromStr for AnswerSet {
+    type Err = InvalidQuestion;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_line(s, 1)
+    }
+}
+
+/// A question that isn't `a`-`z`, at a 1-indexed position in the input
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Invalid question '{found}' at line {line}, column {column}")]
+pub struct InvalidQuestion {
+    pub line: usize,
+    pub column: usize,
+    pub found: char,
+}
+
+/// A [`Group`] using packed [`AnswerSet`]s for each person's declaration
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackedGroup(Vec<AnswerSet>);
+
+impl PackedGroup {
+    /// Parse and validate groups separated by blank lines
+    pub fn parse_all(s: &str) -> Result<Vec<PackedGroup>, InvalidQuestion> {
+        let mut groups = Vec::new();
+        let mut current = Vec::new();
+
+        for (line, text) in (1..).zip(s.lines()) {
+            if text.trim().is_empty() {
+                if !current.is_empty() {
+                    groups.push(PackedGroup(std::mem::take(&mut current)));
+                }
+            } else {
+                current.push(AnswerSet::parse_line(text, line)?);
+            }
+        }
+
+        if !current.is_empty() {
+            groups.push(PackedGroup(current));
+        }
+        Ok(groups)
+    }
+
+    pub fn declarations(&self) -> &[AnswerSet] {
+        &self.0
+    }
+
+    /// The questions anyone in the group answered "yes" to
+    pub fn anyone(&self) -> AnswerSet {
+        self.0.iter().fold(AnswerSet::EMPTY, |all, &set| all | set)
+    }
+
+    /// The questions everyone in the group answered "yes" to
+    pub fn everyone(&self) -> AnswerSet {
+        if self.0.is_empty() {
+            return AnswerSet::EMPTY;
+        }
+        self.0.iter().fold(AnswerSet::ALL, |all, &set| all & set)
+    }
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+
+    type Parsed<'a> = Vec<Group>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type Error = Infallible;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Self::Error> {
+        Ok(Group::parse_iter_from(input).collect())
+    }
+
+    fn part1(groups: &Self::Parsed<'_>) -> Result<Self::Answer1, Self::Error> {
+        Ok(groups.iter().map(Group::count_anyone).sum())
+    }
+
+    fn part2(groups: &Self::Parsed<'_>) -> Result<Self::Answer2, Self::Error> {
+        Ok(groups.iter().map(Group::count_everyone).sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_GROUPS: &str = "abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b
+";
+
+    #[test]
+    fn test_anyone() {
+        let counts = Group::parse_iter_from(TEST_GROUPS)
+            .map(|group| group.count_anyone())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, [3, 3, 3, 1, 1]);
+    }
+
+    #[test]
+    fn test_everyone() {
+        let counts = Group::parse_iter_from(TEST_GROUPS)
+            .map(|group| group.count_everyone())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, [3, 0, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_crlf() {
+        let input = "ab\r\n\r\ncd\r\nc\r\n";
+        let counts = Group::parse_iter_from(input)
+            .map(|group| group.count_everyone())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, [2, 1]);
+
+        let packed = PackedGroup::parse_all(input).expect("Failed to parse");
+        let packed = packed
+            .iter()
+            .map(|g| g.everyone().len())
+            .collect::<Vec<_>>();
+        assert_eq!(packed, counts);
+    }
+
+    #[test]
+    fn test_packed() {
+        let groups = PackedGroup::parse_all(TEST_GROUPS).expect("Failed to parse");
+        let anyone = groups.iter().map(|g| g.anyone().len()).collect::<Vec<_>>();
+        let everyone = groups
+            .iter()
+            .map(|g| g.everyone().len())
+            .collect::<Vec<_>>();
+        assert_eq!(anyone, [3, 3, 3, 1, 1]);
+        assert_eq!(everyone, [3, 0, 1, 1, 1]);
+        assert_eq!(groups[2].everyone().iter().collect::<String>(), "a");
+    }
+
+    #[test]
+    fn test_answer_set() {
+        let ab = AnswerSet::from_str("ab").expect("Failed to parse");
+        let bz = AnswerSet::from_str("zb").expect("Failed to parse");
+        assert_eq!((ab | bz).iter().collect::<String>(), "abz");
+        assert_eq!((ab & bz).iter().collect::<String>(), "b");
+        assert!(!ab.contains('z') && !ab.contains('A'));
+        assert_eq!(AnswerSet::ALL.len(), 26);
+        assert!(AnswerSet::EMPTY.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_question() {
+        assert_eq!(
+            PackedGroup::parse_all("abc\n\nab\naBc\n"),
+            Err(InvalidQuestion {
+                line: 4,
+                column: 2,
+                found: 'B'
+            })
+        );
+    }
+
+    #[test]
+    fn test_solution() {
+        let groups = Day06::parse(TEST_GROUPS).expect("Failed to parse");
+        assert_eq!(groups[1].declarations().len(), 3);
+        assert_eq!(Day06::part1(&groups).expect("Part 1 failed"), 11);
+        assert_eq!(Day06::part2(&groups).expect("Part 2 failed"), 6);
+    }
+}
@@ -8,6 +8,8 @@ use std::{
 
 use daggy::{Dag, NodeIndex};
 
+use crate::Solution;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Color<'a>(pub &'a str);
 
@@ -35,9 +37,11 @@ impl<'a> TryFrom<&'a str> for Rule<'a> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, thiserror::Error)]
 pub enum RuleParseError {
+    #[error("Unexpected trailing input after rule")]
     TooMuch,
+    #[error("Failed to parse rule: {0}")]
     Parsing(String),
 }
 
@@ -123,6 +127,48 @@ impl<'a> FromIterator<Rule<'a>> for Tree<'a> {
     }
 }
 
+/// The color of the bag we're carrying
+pub const MY_BAG: Color<'static> = Color("shiny gold");
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+
+    type Parsed<'a> = Tree<'a>;
+    type Answer1 = usize;
+    type Answer2 = u16;
+    type Error = SolveError;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Self::Error> {
+        input
+            .lines()
+            .map(|line| Rule::try_from(line).map_err(SolveError::from))
+            .collect()
+    }
+
+    fn part1(tree: &Self::Parsed<'_>) -> Result<Self::Answer1, Self::Error> {
+        Ok(tree
+            .bags_that_can_contain(&MY_BAG)
+            .ok_or(SolveError::MissingBag)?
+            .count())
+    }
+
+    fn part2(tree: &Self::Parsed<'_>) -> Result<Self::Answer2, Self::Error> {
+        tree.count_contents_of(&MY_BAG)
+            .ok_or(SolveError::MissingBag)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SolveError {
+    #[error("{0}")]
+    Rule(#[from] RuleParseError),
+
+    #[error("No rule mentions the {} bag", MY_BAG.0)]
+    MissingBag,
+}
+
 mod parser {
     use nom::{
         branch::alt,
@@ -220,4 +266,11 @@ dark violet bags contain no other bags.";
             126
         );
     }
+
+    #[test]
+    fn test_solution() {
+        let tree = Day07::parse(TEST_RULES1).expect("Failed to parse");
+        assert_eq!(Day07::part1(&tree).expect("Part 1 failed"), 4);
+        assert_eq!(Day07::part2(&tree).expect("Part 2 failed"), 32);
+    }
 }
@@ -0,0 +1,139 @@
+//! Acquisition of the puzzle inputs in `inputs/NN`.
+//!
+//! By default every input is embedded in the binary at compile time. With the
+//! `read_inputs` feature enabled they are instead read at runtime from a root
+//! directory, taken from the `AOC_INPUTS` environment variable (or this crate's
+//! `inputs/` directory when unset), so inputs can be swapped without
+//! recompiling.
+
+use std::borrow::Cow;
+#[cfg(feature = "read_inputs")]
+use std::path::{Path, PathBuf};
+
+/// Environment variable used to override the inputs root directory
+pub const ROOT_VAR: &str = "AOC_INPUTS";
+
+pub type Input = Cow<'static, str>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum InputError {
+    #[error("No input is embedded for day {0}")]
+    NotEmbedded(u8),
+
+    #[cfg(feature = "read_inputs")]
+    #[error("Missing input for day {day}: {} does not exist", path.display())]
+    Missing { day: u8, path: PathBuf },
+
+    #[cfg(feature = "read_inputs")]
+    #[error("Failed to read input {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Where inputs are loaded from
+#[derive(Debug, Clone)]
+pub struct Inputs {
+    #[cfg(feature = "read_inputs")]
+    root: PathBuf,
+}
+
+impl Inputs {
+    /// Use the root given by [`ROOT_VAR`], if any
+    pub fn from_env() -> Self {
+        Self {
+            #[cfg(feature = "read_inputs")]
+            root: std::env::var_os(ROOT_VAR)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs")),
+        }
+    }
+
+    #[cfg(feature = "read_inputs")]
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    #[cfg(feature = "read_inputs")]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The path a day's input is read from
+    #[cfg(feature = "read_inputs")]
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.root.join(format!("{:02}", day))
+    }
+
+    #[cfg(feature = "read_inputs")]
+    pub fn load(&self, day: u8) -> Result<Input, InputError> {
+        let path = self.path(day);
+        match std::fs::read_to_string(&path) {
+            Ok(input) => Ok(Cow::Owned(input)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(InputError::Missing { day, path })
+            }
+            Err(source) => Err(InputError::Io { path, source }),
+        }
+    }
+
+    #[cfg(not(feature = "read_inputs"))]
+    pub fn load(&self, day: u8) -> Result<Input, InputError> {
+        embedded(day)
+            .map(Cow::Borrowed)
+            .ok_or(InputError::NotEmbedded(day))
+    }
+}
+
+impl Default for Inputs {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+/// Load the input for a day using the default [`Inputs`]
+pub fn load(day: u8) -> Result<Input, InputError> {
+    Inputs::from_env().load(day)
+}
+
+#[cfg(not(feature = "read_inputs"))]
+fn embedded(day: u8) -> Option<&'static str> {
+    Some(match day {
+        1 => include_str!("../inputs/01"),
+        2 => include_str!("../inputs/02"),
+        3 => include_str!("../inputs/03"),
+        4 => include_str!("../inputs/04"),
+        5 => include_str!("../inputs/05"),
+        6 => include_str!("../inputs/06"),
+        7 => include_str!("../inputs/07"),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_known_day() {
+        let input = load(5).expect("Failed to load input");
+        assert!(input.lines().all(|line| line.len() == 10));
+    }
+
+    #[cfg(not(feature = "read_inputs"))]
+    #[test]
+    fn load_unknown_day() {
+        assert!(matches!(load(25), Err(InputError::NotEmbedded(25))));
+    }
+
+    #[cfg(feature = "read_inputs")]
+    #[test]
+    fn load_missing_day() {
+        let inputs = Inputs::with_root(Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs"));
+        match inputs.load(25) {
+            Err(InputError::Missing { day: 25, path }) => assert!(path.ends_with("inputs/25")),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+}
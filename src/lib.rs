@@ -5,3 +5,10 @@ pub mod day04;
 pub mod day05;
 pub mod day06;
 pub mod day07;
+
+pub mod answers;
+pub mod inputs;
+pub mod reader;
+pub mod runner;
+mod solution;
+pub use solution::Solution;
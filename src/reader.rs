@@ -0,0 +1,178 @@
+//! Line-by-line parsing of inputs from any [`BufRead`], without holding the
+//! whole input in memory
+
+use std::{
+    io::{self, BufRead},
+    ops::Range,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReadError<E: std::error::Error + 'static> {
+    #[error("Failed to read line {line}: {source}")]
+    Io { line: usize, source: io::Error },
+
+    #[error("Line {line}: {source}")]
+    Parse { line: usize, source: E },
+}
+
+impl<E: std::error::Error + 'static> ReadError<E> {
+    /// The 1-indexed line the error occurred on
+    pub fn line(&self) -> usize {
+        match self {
+            ReadError::Io { line, .. } | ReadError::Parse { line, .. } => *line,
+        }
+    }
+}
+
+/// Parses each line of a reader, reusing a single line buffer.
+///
+/// Line endings (LF or CRLF) are stripped before parsing. Like the
+/// `parse_iter_from` functions on `&str` inputs, blank lines are only ignored
+/// at the end of the input: any followed by more data are parsed (and usually
+/// rejected) as empty lines.
+///
+/// A line that isn't valid UTF-8 is reported as an I/O error and skipped, but
+/// any other read failure ends the iterator.
+pub struct ParseLines<R, F> {
+    reader: R,
+    parse: F,
+    buf: String,
+    line: usize,
+    /// Blank lines that haven't been parsed yet, in case more data follows
+    blanks: Range<usize>,
+    /// Whether `buf` holds a line that's waiting on `blanks`, or the error
+    /// reading it in its place
+    held: Option<io::Result<()>>,
+    done: bool,
+}
+
+pub fn parse_lines<R, F, T, E>(reader: R, parse: F) -> ParseLines<R, F>
+where
+    R: BufRead,
+    F: FnMut(&str) -> Result<T, E>,
+{
+    ParseLines {
+        reader,
+        parse,
+        buf: String::new(),
+        line: 0,
+        blanks: 0..0,
+        held: None,
+        done: false,
+    }
+}
+
+impl<R, F, T, E> Iterator for ParseLines<R, F>
+where
+    R: BufRead,
+    F: FnMut(&str) -> Result<T, E>,
+    E: std::error::Error + 'static,
+{
+    type Item = Result<T, ReadError<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.held.is_none() {
+            if self.done {
+                return None;
+            }
+
+            self.buf.clear();
+            match self.reader.read_line(&mut self.buf) {
+                Ok(0) => {
+                    self.done = true;
+                    return None;
+                }
+                Ok(_) => {}
+                // The invalid line has still been consumed, so carry on after it
+                Err(source) if source.kind() == io::ErrorKind::InvalidData => {
+                    self.line += 1;
+                    self.held = Some(Err(source));
+                    continue;
+                }
+                Err(source) => {
+                    self.done = true;
+                    return Some(Err(ReadError::Io {
+                        line: self.line + 1,
+                        source,
+                    }));
+                }
+            }
+
+            self.line += 1;
+            if self.buf.trim_end_matches(['\r', '\n']).is_empty() {
+                if self.blanks.is_empty() {
+                    self.blanks = self.line..self.line;
+                }
+                self.blanks.end = self.line + 1;
+            } else {
+                self.held = Some(Ok(()));
+            }
+        }
+
+        let (line, text) = match self.blanks.next() {
+            Some(blank) => (blank, ""),
+            None => match self.held.take() {
+                Some(Err(source)) => {
+                    return Some(Err(ReadError::Io {
+                        line: self.line,
+                        source,
+                    }))
+                }
+                _ => (self.line, self.buf.trim_end_matches(['\r', '\n'])),
+            },
+        };
+        Some((self.parse)(text).map_err(|source| ReadError::Parse { line, source }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_all(input: &str) -> Vec<Result<u32, (usize, String)>> {
+        parse_lines(input.as_bytes(), str::parse::<u32>)
+            .map(|result| result.map_err(|e| (e.line(), e.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(parse_all("1\r\n2\n3"), [Ok(1), Ok(2), Ok(3)]);
+        assert_eq!(parse_all("1\n2\n\n\r\n"), [Ok(1), Ok(2)]);
+        assert_eq!(parse_all(""), []);
+
+        assert_eq!(
+            parse_all("1\nx\n\n\n5\n"),
+            [
+                Ok(1),
+                Err((2, "Line 2: invalid digit found in string".into())),
+                Err((3, "Line 3: cannot parse integer from empty string".into())),
+                Err((4, "Line 4: cannot parse integer from empty string".into())),
+                Ok(5)
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let input: &[u8] = b"1\n\xff\n3\n";
+        let results = parse_lines(input, str::parse::<u32>).collect::<Vec<_>>();
+        assert!(matches!(
+            results[..],
+            [Ok(1), Err(ReadError::Io { line: 2, .. }), Ok(3)]
+        ));
+
+        // Blank lines before an invalid one are still parsed first
+        let input: &[u8] = b"1\n\n\xff\n\xfe\n4";
+        let lines = parse_lines(input, str::parse::<u32>)
+            .map(|result| match result {
+                Ok(_) => (0, false),
+                Err(e) => (e.line(), matches!(e, ReadError::Io { .. })),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [(0, false), (2, false), (3, true), (4, true), (0, false)]
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Type-erased driving of [`Solution`]s, so any day can be run by number
+
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    day01::Day01, day02::Day02, day03::Day03, day04::Day04, day05::Day05, day06::Day06,
+    day07::Day07, Solution,
+};
+
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = RunError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            other => Err(RunError::UnknownPart(other.to_string())),
+        }
+    }
+}
+
+/// The answer (or failure) for a single part, and how long it took
+#[derive(Debug)]
+pub struct PartOutcome {
+    pub part: Part,
+    pub answer: Result<String, BoxError>,
+    pub elapsed: Duration,
+}
+
+/// The outcome of running some parts of a day against one input
+#[derive(Debug)]
+pub struct DayOutcome {
+    pub day: u8,
+    pub parse_elapsed: Duration,
+    pub parts: Vec<PartOutcome>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RunError {
+    #[error("Day {0} is not implemented")]
+    UnknownDay(u8),
+
+    #[error("Unknown part \"{0}\" (expected 1 or 2)")]
+    UnknownPart(String),
+
+    #[error("Failed to parse input for day {day}: {source}")]
+    Parse { day: u8, source: BoxError },
+}
+
+/// Parse `input` and run the requested `parts` of a solution against it
+pub fn run<S: Solution>(input: &str, parts: &[Part]) -> Result<DayOutcome, RunError> {
+    let start = Instant::now();
+    let parsed = S::parse(input).map_err(|e| RunError::Parse {
+        day: S::DAY,
+        source: Box::new(e),
+    })?;
+    let parse_elapsed = start.elapsed();
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = match part {
+                Part::One => S::part1(&parsed).map(|a| a.to_string()),
+                Part::Two => S::part2(&parsed).map(|a| a.to_string()),
+            };
+            PartOutcome {
+                part,
+                answer: answer.map_err(|e| Box::new(e) as BoxError),
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect();
+
+    Ok(DayOutcome {
+        day: S::DAY,
+        parse_elapsed,
+        parts,
+    })
+}
+
+/// A type-erased handle on a day's [`Solution`]
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub day: u8,
+    run: fn(&str, &[Part]) -> Result<DayOutcome, RunError>,
+}
+
+impl Day {
+    pub const fn of<S: Solution>() -> Self {
+        Self {
+            day: S::DAY,
+            run: run::<S>,
+        }
+    }
+
+    pub fn run(&self, input: &str, parts: &[Part]) -> Result<DayOutcome, RunError> {
+        (self.run)(input, parts)
+    }
+}
+
+impl fmt::Debug for Day {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Day").field("day", &self.day).finish()
+    }
+}
+
+/// Every implemented day, in order
+pub const DAYS: &[Day] = &[
+    Day::of::<Day01>(),
+    Day::of::<Day02>(),
+    Day::of::<Day03>(),
+    Day::of::<Day04>(),
+    Day::of::<Day05>(),
+    Day::of::<Day06>(),
+    Day::of::<Day07>(),
+];
+
+/// Look up an implemented day by number
+pub fn day(day: u8) -> Result<&'static Day, RunError> {
+    DAYS.iter()
+        .find(|d| d.day == day)
+        .ok_or(RunError::UnknownDay(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_are_registered_in_order() {
+        for (i, day) in DAYS.iter().enumerate() {
+            assert_eq!(usize::from(day.day), i + 1);
+        }
+        assert!(matches!(day(25), Err(RunError::UnknownDay(25))));
+    }
+
+    #[test]
+    fn run_single_part() {
+        let outcome = day(5)
+            .expect("Day 5 missing")
+            .run("FBFBBFFRLR\nBFFFBBFRRR", &[Part::One])
+            .expect("Failed to parse");
+
+        assert_eq!(outcome.parts.len(), 1);
+        assert_eq!(outcome.parts[0].part, Part::One);
+        assert_eq!(outcome.parts[0].answer.as_deref().ok(), Some("567"));
+    }
+
+    #[test]
+    fn parse_failure() {
+        assert!(matches!(
+            day(5).expect("Day 5 missing").run("FBFXBFFRLR", &Part::ALL),
+            Err(RunError::Parse { day: 5, .. })
+        ));
+    }
+}
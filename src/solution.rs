@@ -0,0 +1,25 @@
+use std::fmt::Display;
+
+/// A puzzle solution for a single day.
+///
+/// Parsing is split out from the two parts so that the parsed form of an input
+/// can be shared between them (and timed separately). Parsed data may borrow
+/// from the input text, hence the generic lifetime on [`Solution::Parsed`].
+pub trait Solution {
+    /// The day of the advent calendar this solves (1-indexed)
+    const DAY: u8;
+
+    /// The parsed representation of the puzzle input
+    type Parsed<'a>;
+
+    type Answer1: Display;
+    type Answer2: Display;
+
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Self::Error>;
+
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<Self::Answer1, Self::Error>;
+
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<Self::Answer2, Self::Error>;
+}